@@ -7,24 +7,43 @@
 
 use std::path::Path;
 
-use clap::{self, crate_authors, crate_version, App, AppSettings, Arg};
+use crate::OverwritePolicy;
+use clap::{self, crate_authors, crate_version, App, AppSettings, Arg, SubCommand};
 
 const USAGE: &str = "
-    brix [LANGUAGE] [CONFIG NAME] [PROJECT] [MODULE]
+    brix [LANGUAGE] [CONFIG NAME] [ARGS]...
     brix [OPTIONS] --config-dir | -d [CONFIG DIRECTORY]
     brix [OPTIONS] --workdir | -w [WORKING DIRECTORY]
+    brix [OPTIONS] --set [KEY=VALUE] --set-json [KEY=JSON]
+    brix [OPTIONS] --dry-run
+    brix [OPTIONS] --no-rollback
+    brix [OPTIONS] --overwrite [POLICY]
+    brix [OPTIONS] --yes | --no-input
+    brix undo [--force]
+    brix update
 ";
 
 // Positional arguments
 pub const LANGUAGE: &str = "LANGUAGE";
 pub const CONFIG_NAME: &str = "CONFIG_NAME";
-pub const PROJECT: &str = "PROJECT";
-pub const MODULE: &str = "MODULE";
+pub const ARGS: &str = "ARGS";
 
 // Flags
 pub const CONFIG_DIR: &str = "CONFIG_DIR";
 pub const WORKDIR: &str = "WORKDIR";
 pub const LOG_LEVEL: &str = "LOG_LEVEL";
+pub const SET: &str = "SET";
+pub const SET_JSON: &str = "SET_JSON";
+pub const DRY_RUN: &str = "DRY_RUN";
+pub const NO_ROLLBACK: &str = "NO_ROLLBACK";
+pub const OVERWRITE: &str = "OVERWRITE";
+pub const YES: &str = "YES";
+pub const NO_INPUT: &str = "NO_INPUT";
+pub const FORCE: &str = "FORCE";
+
+// Subcommands
+pub const UNDO: &str = "undo";
+pub const UPDATE: &str = "update";
 
 /// Creates the clap application and sets args
 pub fn app() -> App<'static, 'static> {
@@ -34,20 +53,38 @@ pub fn app() -> App<'static, 'static> {
         .max_term_width(100)
         .setting(AppSettings::UnifiedHelpMessage)
         .setting(AppSettings::ArgRequiredElseHelp)
+        .setting(AppSettings::SubcommandsNegateReqs)
         .usage(USAGE)
         .help_message("Prints this message");
 
     app = app.arg(arg_language());
     app = app.arg(arg_config_name());
-    app = app.arg(arg_project());
-    app = app.arg(arg_module());
+    app = app.arg(arg_args());
     app = app.arg(flag_config_dir());
     app = app.arg(flag_log_level());
     app = app.arg(flag_workdir());
+    app = app.arg(flag_set());
+    app = app.arg(flag_set_json());
+    app = app.arg(flag_dry_run());
+    app = app.arg(flag_no_rollback());
+    app = app.arg(flag_overwrite());
+    app = app.arg(flag_yes());
+    app = app.arg(flag_no_input());
+    app = app.subcommand(subcommand_undo());
+    app = app.subcommand(subcommand_update());
 
     app
 }
 
+fn subcommand_undo() -> App<'static, 'static> {
+    const ABOUT: &str =
+        "Deletes the files created by the last run and restores the files it modified";
+    const FORCE_HELP: &str = "Undoes the run even if files were changed since they were generated";
+    SubCommand::with_name(UNDO)
+        .about(ABOUT)
+        .arg(Arg::with_name(FORCE).help(FORCE_HELP).long("force"))
+}
+
 fn arg_language() -> Arg<'static, 'static> {
     const HELP: &str = "The programming language you're generating code for. Directory under -d";
     Arg::with_name(LANGUAGE)
@@ -64,20 +101,12 @@ fn arg_config_name() -> Arg<'static, 'static> {
         .required(true)
 }
 
-fn arg_project() -> Arg<'static, 'static> {
-    const HELP: &str = "The name of the project you're generating code for";
-    Arg::with_name(PROJECT)
-        .value_name("project")
+fn arg_args() -> Arg<'static, 'static> {
+    const HELP: &str = "The arguments declared by the config, defaults to the project and module";
+    Arg::with_name(ARGS)
+        .value_name("args")
         .help(HELP)
-        .required(true)
-}
-
-fn arg_module() -> Arg<'static, 'static> {
-    const HELP: &str = "The name of the module to be created within the project";
-    Arg::with_name(MODULE)
-        .value_name("module")
-        .help(HELP)
-        .required(true)
+        .multiple(true)
 }
 
 fn flag_config_dir() -> Arg<'static, 'static> {
@@ -118,6 +147,92 @@ fn flag_log_level() -> Arg<'static, 'static> {
         .possible_values(&["off", "error", "warn", "info", "debug", "trace"])
 }
 
+fn flag_set() -> Arg<'static, 'static> {
+    const HELP: &str = "Overrides a context value as a string, dotted keys set nested values e.g. db.host=localhost";
+    Arg::with_name(SET)
+        .value_name("key=value")
+        .help(HELP)
+        .long("set")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .validator(is_key_value)
+}
+
+fn flag_set_json() -> Arg<'static, 'static> {
+    const HELP: &str =
+        "Overrides a context value with JSON, dotted keys set nested values e.g. db.port=5432";
+    Arg::with_name(SET_JSON)
+        .value_name("key=json")
+        .help(HELP)
+        .long("set-json")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .validator(is_key_json)
+}
+
+fn flag_dry_run() -> Arg<'static, 'static> {
+    const HELP: &str =
+        "Prints what each command would do without writing any files or running any processes";
+    Arg::with_name(DRY_RUN).help(HELP).long("dry-run")
+}
+
+fn flag_no_rollback() -> Arg<'static, 'static> {
+    const HELP: &str = "Keeps the files written by earlier commands when a command fails instead of restoring them";
+    Arg::with_name(NO_ROLLBACK).help(HELP).long("no-rollback")
+}
+
+fn flag_overwrite() -> Arg<'static, 'static> {
+    const HELP: &str = "What to do when a destination already exists, for commands that don't set overwrite themselves";
+    Arg::with_name(OVERWRITE)
+        .value_name("policy")
+        .help(HELP)
+        .long("overwrite")
+        .takes_value(true)
+        .possible_values(&OverwritePolicy::NAMES)
+}
+
+fn flag_yes() -> Arg<'static, 'static> {
+    const HELP: &str =
+        "Never prompts, answering every prompt with its default and overwriting files instead of asking";
+    Arg::with_name(YES).help(HELP).long("yes").short("y")
+}
+
+fn flag_no_input() -> Arg<'static, 'static> {
+    const HELP: &str = "Never prompts, answering every prompt with its default. Implied when stdin isn't a terminal";
+    Arg::with_name(NO_INPUT).help(HELP).long("no-input")
+}
+
+fn subcommand_update() -> App<'static, 'static> {
+    const ABOUT: &str = "Runs the configs in .brix-answers.yml again with the same answers, merging the changes into the generated files";
+    SubCommand::with_name(UPDATE).about(ABOUT)
+}
+
+/// Splits a `key=value` pair on the first `=`, the key must not be empty.
+pub(crate) fn split_key_value(v: &str) -> Option<(&str, &str)> {
+    let mut split = v.splitn(2, '=');
+    match (split.next(), split.next()) {
+        (Some(key), Some(value)) if !key.is_empty() => Some((key, value)),
+        _ => None,
+    }
+}
+
+fn is_key_value(v: String) -> Result<(), String> {
+    match split_key_value(&v) {
+        Some(_) => Ok(()),
+        None => Err(format!("'{}' is not in the form key=value", v)),
+    }
+}
+
+fn is_key_json(v: String) -> Result<(), String> {
+    let (_, json) = split_key_value(&v).ok_or(format!("'{}' is not in the form key=json", v))?;
+    match serde_json::from_str::<serde_json::Value>(json) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(format!("'{}' is not valid JSON: {}", json, err)),
+    }
+}
+
 fn is_valid_path(v: String) -> Result<(), String> {
     let path = Path::new(&v);
     if path.exists() {
@@ -9,11 +9,38 @@ use crate::error;
 use clap::ArgMatches;
 use colored::*;
 use log::LevelFilter;
+use serde_json::Value;
 use std::borrow::Cow;
 use std::fmt::{self, Display, Formatter};
+use std::io::{self, IsTerminal};
 use std::path::PathBuf;
 
 use crate::app;
+use crate::OverwritePolicy;
+
+/// What Brix was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Runs the config for the language and config name.
+    Generate,
+    /// Takes back the last run with `brix undo`.
+    Undo { force: bool },
+    /// Runs the configs in `.brix-answers.yml` again with `brix update`,
+    /// merging the output into the files that were generated before.
+    Update,
+}
+
+/// How prompts are answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompts {
+    /// Prompts are shown in the terminal.
+    Interactive,
+    /// Every prompt is answered with its default, with `--no-input` or when stdin isn't a terminal.
+    /// Prompts without a default fail instead.
+    Defaults,
+    /// Like [Prompts::Defaults], but files are overwritten instead of asking, with `--yes`.
+    Yes,
+}
 
 /// The core config struct used within Brix's lifecycle.
 /// Mostly the direct output of the CLI args and flags,
@@ -21,15 +48,22 @@ use crate::app;
 pub struct Config {
     pub language: String,
     pub config_name: String,
-    pub project: String,
-    pub module: String,
+    pub args: Vec<String>,
+    /// Context overrides from `--set` and `--set-json` in the order they were passed.
+    pub overrides: Vec<(String, Value)>,
 
     pub config_dir: Option<PathBuf>,
     pub workdir: PathBuf,
     pub home_dir: Option<PathBuf>,
     pub log_level: log::LevelFilter,
-
-    pub raw_matches: ArgMatches<'static>,
+    /// Whether to only print what the commands would do with `--dry-run`.
+    pub dry_run: bool,
+    /// Whether to keep the changes of a failed run with `--no-rollback`.
+    pub no_rollback: bool,
+    /// The policy for commands that don't set `overwrite` themselves with `--overwrite`.
+    pub overwrite: Option<OverwritePolicy>,
+    pub prompts: Prompts,
+    pub mode: Mode,
 }
 
 impl Config {
@@ -44,13 +78,39 @@ impl Config {
             std::process::exit(2);
         });
 
-        let language = matches.value_of_lossy(app::LANGUAGE).unwrap().to_string();
+        // Neither are required with a subcommand
+        let language = matches
+            .value_of_lossy(app::LANGUAGE)
+            .unwrap_or_default()
+            .to_string();
         let config_name = matches
             .value_of_lossy(app::CONFIG_NAME)
-            .unwrap()
+            .unwrap_or_default()
             .to_string();
-        let project = matches.value_of_lossy(app::PROJECT).unwrap().to_string();
-        let module = matches.value_of_lossy(app::MODULE).unwrap().to_string();
+        let args = matches.values_of_lossy(app::ARGS).unwrap_or_default();
+
+        let mut overrides = Vec::new();
+        for (flag, is_json) in [(app::SET, false), (app::SET_JSON, true)].iter() {
+            if let (Some(indices), Some(values)) =
+                (matches.indices_of(flag), matches.values_of_lossy(flag))
+            {
+                for (index, raw) in indices.zip(values.iter()) {
+                    // Both have already been checked by the clap validators
+                    let (key, value) = app::split_key_value(raw).unwrap();
+                    let value = if *is_json {
+                        serde_json::from_str(value).unwrap()
+                    } else {
+                        Value::from(value)
+                    };
+                    overrides.push((index, key.to_string(), value));
+                }
+            }
+        }
+        overrides.sort_by_key(|(index, _, _)| *index);
+        let overrides = overrides
+            .into_iter()
+            .map(|(_, key, value)| (key, value))
+            .collect();
 
         let config_dir = matches
             .value_of_lossy(app::CONFIG_DIR)
@@ -66,17 +126,71 @@ impl Config {
             .value_of_lossy(app::LOG_LEVEL)
             .unwrap_or(Cow::from("off"))
             .to_string();
+        let dry_run = matches.is_present(app::DRY_RUN);
+        let no_rollback = matches.is_present(app::NO_ROLLBACK);
+        // Already checked against the possible values by clap
+        let overwrite = matches
+            .value_of(app::OVERWRITE)
+            .map(|policy| policy.parse().unwrap());
+        let prompts = if matches.is_present(app::YES) {
+            Prompts::Yes
+        } else if matches.is_present(app::NO_INPUT) || !io::stdin().is_terminal() {
+            Prompts::Defaults
+        } else {
+            Prompts::Interactive
+        };
+        let mode = match matches.subcommand() {
+            (app::UNDO, Some(undo)) => Mode::Undo {
+                force: undo.is_present(app::FORCE),
+            },
+            (app::UPDATE, Some(_)) => Mode::Update,
+            _ => Mode::Generate,
+        };
 
         Self {
-            raw_matches: matches,
             language,
             config_name,
-            project,
+            args,
+            overrides,
             config_dir,
             workdir,
             home_dir,
             log_level: log_level_to_struct(&log_level),
-            module,
+            dry_run,
+            no_rollback,
+            overwrite,
+            prompts,
+            mode,
+        }
+    }
+
+    /// Whether prompts are shown, otherwise they're answered with their defaults.
+    pub fn is_interactive(&self) -> bool {
+        self.prompts == Prompts::Interactive
+    }
+
+    /// Creates a config for running another config with the same options,
+    /// with `overrides` standing in for its arguments and inputs.
+    pub fn for_config(
+        &self,
+        language: &str,
+        config_name: &str,
+        overrides: Vec<(String, Value)>,
+    ) -> Self {
+        Self {
+            language: language.to_string(),
+            config_name: config_name.to_string(),
+            args: vec![],
+            overrides,
+            config_dir: self.config_dir.clone(),
+            workdir: self.workdir.clone(),
+            home_dir: self.home_dir.clone(),
+            log_level: self.log_level,
+            dry_run: self.dry_run,
+            no_rollback: self.no_rollback,
+            overwrite: self.overwrite,
+            prompts: self.prompts,
+            mode: self.mode.clone(),
         }
     }
 }
@@ -106,13 +220,17 @@ impl std::default::Default for Config {
         Self {
             language: s!(),
             config_name: s!(),
-            project: s!(),
-            module: s!(),
+            args: vec![],
+            overrides: vec![],
             config_dir: None,
             workdir: std::env::current_dir().unwrap(),
             home_dir: None,
             log_level: LevelFilter::Off,
-            raw_matches: ArgMatches::default(),
+            dry_run: false,
+            no_rollback: false,
+            overwrite: None,
+            prompts: Prompts::Interactive,
+            mode: Mode::Generate,
         }
     }
 }
@@ -121,8 +239,8 @@ impl Display for Config {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         write!(
             formatter,
-            "[LANGUAGE: {}, CONFIG_NAME: {}, PROJECT: {}, MODULE: {}, CONFIG_DIR: {:?}]",
-            self.language, self.config_name, self.project, self.module, self.config_dir
+            "[LANGUAGE: {}, CONFIG_NAME: {}, ARGS: {:?}, CONFIG_DIR: {:?}]",
+            self.language, self.config_name, self.args, self.config_dir
         )
     }
 }
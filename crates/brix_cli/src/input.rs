@@ -0,0 +1,41 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Module for all things relating to text and confirmation prompts.
+
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use dialoguer::console::Term;
+use dialoguer::{Confirm, Input};
+
+use brix_errors::BrixError;
+
+/// Basic wrapper around the `dialoguer::Input` prompt with stdout.
+/// The validator is ran against each answer and the prompt is repeated until it passes.
+pub fn do_input<T, V>(prompt: &str, default: Option<T>, validator: V) -> Result<T, BrixError>
+where
+    T: Clone + ToString + FromStr,
+    <T as FromStr>::Err: Debug + ToString,
+    V: FnMut(&T) -> Result<(), String>,
+{
+    let mut input = Input::<T>::new();
+    input.with_prompt(prompt).validate_with(validator);
+    if let Some(default) = default {
+        input.default(default);
+    }
+
+    Ok(input.interact_text_on(&Term::stdout())?)
+}
+
+/// Basic wrapper around the `dialoguer::Confirm` prompt with stdout.
+pub fn do_confirm(prompt: &str, default: bool) -> Result<bool, BrixError> {
+    let confirm = Confirm::new()
+        .with_prompt(prompt)
+        .default(default)
+        .interact_on(&Term::stdout())?;
+
+    Ok(confirm)
+}
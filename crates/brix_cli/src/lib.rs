@@ -12,10 +12,13 @@ use brix_errors::BrixError;
 mod app;
 mod args;
 mod config;
+mod overwrite;
 mod print;
 
+pub mod input;
 pub mod select;
 
 pub use args::clap_matches;
 pub use clap::ArgMatches;
-pub use config::Config;
+pub use config::{Config, Mode, Prompts};
+pub use overwrite::OverwritePolicy;
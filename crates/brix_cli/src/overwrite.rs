@@ -0,0 +1,95 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [OverwritePolicy], which decides what happens when a destination already exists.
+
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// What a command does when its destination already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverwritePolicy {
+    /// Overwrites the file.
+    Always,
+    /// Leaves the file alone.
+    Never,
+    /// Shows a diff and asks whether to overwrite the file.
+    #[default]
+    Ask,
+    /// Renames the file to `<name>.orig` before writing.
+    Backup,
+    /// Overwrites the file only if it still has what Brix last wrote to it.
+    IfUnchanged,
+    /// Merges the new contents into the file like `brix update`.
+    Merge,
+}
+
+impl OverwritePolicy {
+    /// The name of every policy, as accepted by `--overwrite` and the `overwrite` param.
+    pub const NAMES: [&'static str; 6] =
+        ["always", "never", "ask", "backup", "if-unchanged", "merge"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OverwritePolicy::Always => "always",
+            OverwritePolicy::Never => "never",
+            OverwritePolicy::Ask => "ask",
+            OverwritePolicy::Backup => "backup",
+            OverwritePolicy::IfUnchanged => "if-unchanged",
+            OverwritePolicy::Merge => "merge",
+        }
+    }
+}
+
+impl FromStr for OverwritePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(OverwritePolicy::Always),
+            "never" => Ok(OverwritePolicy::Never),
+            "ask" => Ok(OverwritePolicy::Ask),
+            "backup" => Ok(OverwritePolicy::Backup),
+            "if-unchanged" => Ok(OverwritePolicy::IfUnchanged),
+            "merge" => Ok(OverwritePolicy::Merge),
+            _ => Err(format!(
+                "unknown overwrite policy '{}', expected one of {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl Display for OverwritePolicy {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Serialize for OverwritePolicy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// Booleans are still accepted, `true` being `always` and `false` being `never`.
+impl<'de> Deserialize<'de> for OverwritePolicy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Bool(true) => Ok(OverwritePolicy::Always),
+            Value::Bool(false) => Ok(OverwritePolicy::Never),
+            Value::String(s) => s.parse().map_err(de::Error::custom),
+            other => Err(de::Error::custom(format!(
+                "expected a boolean or one of {} for overwrite, got '{}'",
+                Self::NAMES.join(", "),
+                other
+            ))),
+        }
+    }
+}
@@ -6,7 +6,7 @@
 //! Module for all things relating to select prompts.
 
 use dialoguer::console::Term;
-use dialoguer::Select;
+use dialoguer::{MultiSelect, Select};
 
 use brix_errors::BrixError;
 
@@ -22,3 +22,39 @@ pub fn do_select(items: Vec<&str>) -> Result<usize, BrixError> {
         None => Err(BrixError::with("no option selected!")),
     }
 }
+
+/// Wrapper around the `dialoguer::Select` prompt with a prompt and default item.
+pub fn do_select_prompt(
+    prompt: &str,
+    items: Vec<&str>,
+    default: usize,
+) -> Result<usize, BrixError> {
+    let selection = Select::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .default(default)
+        .interact_on_opt(&Term::stdout())?;
+
+    match selection {
+        Some(index) => Ok(index),
+        None => Err(BrixError::with("no option selected!")),
+    }
+}
+
+/// Basic wrapper around the `dialoguer::MultiSelect` prompt with stdout.
+pub fn do_multi_select(
+    prompt: &str,
+    items: Vec<&str>,
+    defaults: &[bool],
+) -> Result<Vec<usize>, BrixError> {
+    let selection = MultiSelect::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .defaults(defaults)
+        .interact_on_opt(&Term::stdout())?;
+
+    match selection {
+        Some(indexes) => Ok(indexes),
+        None => Err(BrixError::with("no option selected!")),
+    }
+}
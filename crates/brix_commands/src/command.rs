@@ -3,33 +3,56 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
-use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
 use std::format;
-use std::fs::create_dir_all;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
+use colored::*;
 use dialoguer::console::Term;
 use dialoguer::Confirm;
 use log::{debug, error, info};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use validator::ValidationErrors;
+use serde_json::Value;
+use validator::Validate;
 
+use crate::dir;
+use crate::merge::{diff, merge};
+use brix_cli::{Mode, OverwritePolicy, Prompts};
+use brix_common::filesystem::normalize;
+use brix_common::history::{content_hash, Manifest};
 use brix_common::AppContext;
-use brix_errors::BrixError;
+use brix_errors::{BrixError, BrixErrorKind};
 
 pub mod copy;
 pub mod exec;
+pub mod generate;
+pub mod inject;
 pub mod mkdir;
+pub mod r#move;
+pub mod plugin;
+pub mod remove;
 pub mod search_replace;
+pub mod symlink;
 pub mod template;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod write;
 
 /// The base command trait that all commands must implement.
 pub trait Command {
-    fn run(&self, pcp: ProcessedCommandParams, app_context: &AppContext) -> Result<(), BrixError>;
+    fn run(&self, params: CommandParams, app_context: &AppContext) -> Result<(), BrixError>;
+    /// Describes what [run](Command::run) would do without writing any files or spawning any processes.
+    fn plan(
+        &self,
+        params: CommandParams,
+        app_context: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError>;
     fn name(&self) -> String;
 }
 
-/// A trait that derives from `Command` that prompt to overwrite the destination file.
+/// A trait that derives from `Command` for commands that write to a destination,
+/// which decides what happens when the destination exists with an [OverwritePolicy].
 pub trait OverwritableCommand: Command {
     type Params: OverwritableParams + 'static;
 
@@ -61,35 +84,226 @@ pub trait OverwritableCommand: Command {
         Ok(())
     }
 
-    fn from(&self, pcp: ProcessedCommandParams) -> Result<Self::Params, ValidationErrors>;
+    fn from(&self, params: CommandParams) -> Result<Self::Params, BrixError>;
+
+    /// Every file that writing would create or overwrite, defaults to just the destination.
+    fn destinations(
+        &self,
+        params: &Self::Params,
+        app_context: &AppContext,
+    ) -> Result<Vec<PathBuf>, BrixError> {
+        Ok(vec![dir!(app_context.config.workdir, params.destination())])
+    }
+
+    /// The source files that are left out of writing, such as the ones `copy` excludes,
+    /// so that `--dry-run` can list them.
+    fn ignored(
+        &self,
+        _params: &Self::Params,
+        _app_context: &AppContext,
+    ) -> Result<Vec<PathBuf>, BrixError> {
+        Ok(vec![])
+    }
+
+    /// What would be written to the destination, for commands that write a single file.
+    /// `brix update` merges this into the destination instead of overwriting it.
+    fn contents(
+        &self,
+        _params: &Self::Params,
+        _app_context: &AppContext,
+    ) -> Result<Option<Vec<u8>>, BrixError> {
+        Ok(None)
+    }
+
+    /// Whether the command writes several files and merges each of them itself with
+    /// [merge_files](OverwritableCommand::merge_files), since it has no single [contents](OverwritableCommand::contents).
+    fn merges_files(
+        &self,
+        _params: &Self::Params,
+        _app_context: &AppContext,
+    ) -> Result<bool, BrixError> {
+        Ok(false)
+    }
+
+    /// Merges the update into every file that already exists and writes the rest.
+    fn merge_files(
+        &self,
+        _params: Self::Params,
+        _app_context: &AppContext,
+    ) -> Result<(), BrixError> {
+        Err(unsupported_merge(&self.name_inner()))
+    }
 
     fn write_impl(&self, params: Self::Params, app_context: &AppContext) -> Result<(), BrixError>;
 }
 
+/// Merges the update into the file with what was rendered for it last time as the base,
+/// leaving conflict markers where both changed the same lines.
+/// A file that was never rendered has no base, so it's backed up and overwritten instead.
+fn merge_update(path: &Path, update: Vec<u8>, app_context: &AppContext) -> Result<(), BrixError> {
+    let workdir = &app_context.config.workdir;
+    let relative = path.strip_prefix(workdir).unwrap_or(path);
+    let fs = &*app_context.fs;
+    let current = fs.read(path)?;
+    let base = match Manifest::last_render(fs, workdir, relative)? {
+        Some(base) => base,
+        None if current == update => {
+            info!("unchanged: '{}'", path.display());
+            app_context.journal.record_render(path, update);
+            return Ok(());
+        }
+        // Every line would conflict without a base
+        None => {
+            let backup = backup_path(path);
+            app_context.journal.record(&backup)?;
+            info!(
+                "backing up: '{}', there is no earlier render to merge with",
+                backup.display()
+            );
+            fs.rename(path, &backup)?;
+            fs.write(path, &update)?;
+            app_context.journal.record_render(path, update);
+            return Ok(());
+        }
+    };
+
+    let merged = merge(
+        &String::from_utf8_lossy(&base),
+        &String::from_utf8_lossy(&current),
+        &String::from_utf8_lossy(&update),
+    );
+    app_context.journal.record_render(path, update);
+    if merged.contents.as_bytes() == current.as_slice() {
+        info!("unchanged: '{}'", path.display());
+        return Ok(());
+    }
+
+    info!("merging: '{}'", path.display());
+    fs.write(path, merged.contents.as_bytes())?;
+    if merged.conflicts > 0 {
+        println!(
+            "{} {} conflict(s) in '{}'",
+            "CONFLICT".yellow(),
+            merged.conflicts,
+            relative.display()
+        );
+    }
+    Ok(())
+}
+
+/// The policy of the command, falling back to `--overwrite` and then to asking.
+fn overwrite_policy(params: &impl OverwritableParams, app_context: &AppContext) -> OverwritePolicy {
+    params
+        .overwrite()
+        .or(app_context.config.overwrite)
+        .unwrap_or_default()
+}
+
+/// The path an existing file is moved to by the `backup` policy, `<name>.orig`.
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".orig");
+    path.with_file_name(name)
+}
+
+/// Whether every existing file still has what Brix last wrote to it, according to the history.
+/// Files Brix has no record of writing count as changed.
+fn is_unchanged(paths: &[PathBuf], app_context: &AppContext) -> Result<bool, BrixError> {
+    let workdir = &app_context.config.workdir;
+    let fs = &*app_context.fs;
+    for path in paths.iter().filter(|path| fs.is_file(path)) {
+        let relative = path.strip_prefix(workdir).unwrap_or(path);
+        let hash = Manifest::last_hash(fs, workdir, relative)?;
+        if hash != Some(content_hash(&fs.read(path)?)) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn check_source(
+    params: &impl OverwritableParams,
+    app_context: &AppContext,
+) -> Result<(), BrixError> {
+    match params.source() {
+        Some(source) if !app_context.fs.exists(&source) => Err(BrixError::with(&format!(
+            "source '{}' does not exist",
+            source.display()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Joins the path to the working directory like `dir!`, refusing any path that isn't inside of it
+/// since commands that remove or move paths could otherwise reach anything.
+/// The path itself can be a link, which is used rather than what it points to.
+fn workdir_path(path: &Path, app_context: &AppContext) -> Result<PathBuf, BrixError> {
+    let fs = &app_context.fs;
+    let workdir = normalize(&app_context.config.workdir);
+    let joined = normalize(&dir!(app_context.config.workdir, path));
+    let inside = match joined.strip_prefix(&workdir) {
+        Ok(rest) => {
+            rest.components().next().is_some()
+                && !rest.components().any(|c| c == Component::ParentDir)
+        }
+        Err(_) => false,
+    };
+    // A link to a directory along the way could still lead out of it
+    let inside = inside
+        && fs
+            .canonicalize(joined.parent().unwrap())?
+            .starts_with(fs.canonicalize(&workdir)?);
+
+    match inside {
+        true => Ok(joined),
+        false => Err(BrixError::with(&format!(
+            "refusing to use '{}', it isn't inside of the working directory '{}'",
+            path.display(),
+            app_context.config.workdir.display()
+        ))),
+    }
+}
+
+fn unsupported_merge(name: &str) -> BrixError {
+    BrixError::with(&format!(
+        "the merge overwrite policy isn't supported by the {} command",
+        name
+    ))
+}
+
 impl<T> Command for T
 where
     T: OverwritableCommand,
 {
-    fn run(&self, pcp: ProcessedCommandParams, app_context: &AppContext) -> Result<(), BrixError> {
-        let params = self.from(pcp)?;
-
-        if !params.source().exists() {
-            return Err(BrixError::with(&format!(
-                "source '{}' does not exist",
-                &params.source().display()
-            )));
+    fn run(&self, params: CommandParams, app_context: &AppContext) -> Result<(), BrixError> {
+        let params = self.from(params)?;
+
+        check_source(&params, app_context)?;
+
+        // Record what's there now in case a later command fails
+        for destination in self.destinations(&params, app_context)? {
+            app_context.journal.record(&destination)?;
+        }
+
+        // Updates are merged into files that already exist
+        if app_context.config.mode == Mode::Update {
+            let path = dir!(app_context.config.workdir, params.destination());
+            if app_context.fs.is_file(&path) {
+                if let Some(update) = self.contents(&params, app_context)? {
+                    return merge_update(&path, update, app_context);
+                }
+            }
+            if self.merges_files(&params, app_context)? {
+                return self.merge_files(params, app_context);
+            }
         }
 
         let dest = &params.destination();
-        let parent = &dest.parent();
-        debug!(
-            "overwrite param '{}' '{}'",
-            params.overwrite().is_some(),
-            params.overwrite().unwrap()
-        );
-        if !(parent.is_some() && parent.unwrap().exists()) && parent.is_some() {
+        let path = dir!(app_context.config.workdir, dest);
+        let parent = &path.parent();
+        if !(parent.is_some() && app_context.fs.exists(parent.unwrap())) && parent.is_some() {
             debug!("creating directory '{}'", parent.unwrap().display());
-            if let Err(e) = create_dir_all(parent.unwrap()) {
+            if let Err(e) = app_context.fs.create_dir_all(parent.unwrap()) {
                 return Err(BrixError::with(&format!(
                     "unable to create '{}': {}",
                     parent.unwrap().display(),
@@ -98,18 +312,127 @@ where
             }
         }
 
-        if params.overwrite().is_some() {
-            let overwrite = params.overwrite().unwrap();
-            return if (dest.exists() && overwrite) || !dest.exists() {
+        if !app_context.fs.exists(&path) {
+            return self.write(params, app_context);
+        }
+
+        let policy = overwrite_policy(&params, app_context);
+        debug!("overwrite policy '{}'", policy);
+        match policy {
+            OverwritePolicy::Always => self.write(params, app_context),
+            OverwritePolicy::Never => self.skip_write(dest),
+            OverwritePolicy::Ask if app_context.config.prompts == Prompts::Yes => {
                 self.write(params, app_context)
-            } else {
-                self.skip_write(dest)
-            };
+            }
+            // Without prompts the file is kept, the same as the default answer
+            OverwritePolicy::Ask if !app_context.config.is_interactive() => self.skip_write(dest),
+            OverwritePolicy::Ask => {
+                if let Some(update) = self.contents(&params, app_context)? {
+                    let current = app_context.fs.read(&path)?;
+                    println!(
+                        "{}",
+                        diff(
+                            &dest.display().to_string(),
+                            &String::from_utf8_lossy(&current),
+                            &String::from_utf8_lossy(&update),
+                        )
+                    );
+                }
+                if self.ask_to_write(dest) {
+                    self.write(params, app_context)
+                } else {
+                    self.skip_write(dest)
+                }
+            }
+            OverwritePolicy::Backup => {
+                for destination in self.destinations(&params, app_context)? {
+                    if app_context.fs.is_file(&destination) {
+                        let backup = backup_path(&destination);
+                        app_context.journal.record(&backup)?;
+                        info!("backing up: '{}'", backup.display());
+                        app_context.fs.rename(&destination, &backup)?;
+                    }
+                }
+                self.write(params, app_context)
+            }
+            OverwritePolicy::IfUnchanged => {
+                if is_unchanged(&self.destinations(&params, app_context)?, app_context)? {
+                    self.write(params, app_context)
+                } else {
+                    info!(
+                        "skipping: '{}', changed since it was generated",
+                        dest.display()
+                    );
+                    Ok(())
+                }
+            }
+            OverwritePolicy::Merge => match self.contents(&params, app_context)? {
+                Some(update) => merge_update(&path, update, app_context),
+                None => self.merge_files(params, app_context),
+            },
         }
-        if self.ask_to_write(dest) {
-            return self.write(params, app_context);
+    }
+
+    fn plan(
+        &self,
+        params: CommandParams,
+        app_context: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        let params = self.from(params)?;
+
+        check_source(&params, app_context)?;
+
+        let policy = overwrite_policy(&params, app_context);
+        let destinations = self.destinations(&params, app_context)?;
+        let can_merge = self.contents(&params, app_context)?.is_some()
+            || self.merges_files(&params, app_context)?;
+        let can_merge = match (app_context.config.mode == Mode::Update, policy) {
+            (true, _) => can_merge,
+            (false, OverwritePolicy::Merge) if !can_merge => {
+                return Err(unsupported_merge(&self.name_inner()))
+            }
+            (false, OverwritePolicy::Merge) => true,
+            _ => false,
+        };
+        let prompts = app_context.config.prompts;
+        let unchanged =
+            policy == OverwritePolicy::IfUnchanged && is_unchanged(&destinations, app_context)?;
+
+        let ignored = self.ignored(&params, app_context)?;
+        let workdir = &app_context.config.workdir;
+        let has_base = |dest: &Path| -> Result<bool, BrixError> {
+            let relative = dest.strip_prefix(workdir).unwrap_or(dest);
+            Ok(Manifest::last_render(&*app_context.fs, workdir, relative)?.is_some())
         };
-        return self.skip_write(dest);
+        let actions = destinations
+            .into_iter()
+            .map(|dest| {
+                Ok(match (app_context.fs.exists(&dest), policy) {
+                    (false, _) => PlannedAction::Create(dest),
+                    (true, _) if can_merge && has_base(&dest)? => PlannedAction::Merge(dest),
+                    // There's nothing to merge with, see `merge_update`
+                    (true, _) if can_merge => PlannedAction::Backup(dest),
+                    (true, OverwritePolicy::Always) => PlannedAction::Overwrite(dest),
+                    (true, OverwritePolicy::IfUnchanged) if unchanged => {
+                        PlannedAction::Overwrite(dest)
+                    }
+                    (true, OverwritePolicy::Backup) => PlannedAction::Backup(dest),
+                    (true, OverwritePolicy::Ask) if prompts == Prompts::Yes => {
+                        PlannedAction::Overwrite(dest)
+                    }
+                    (true, OverwritePolicy::Ask) if prompts == Prompts::Interactive => {
+                        PlannedAction::Ask(dest)
+                    }
+                    (true, _) => PlannedAction::Skip(dest),
+                })
+            })
+            .chain(
+                ignored
+                    .into_iter()
+                    .map(|path| Ok(PlannedAction::Ignore(path))),
+            )
+            .collect::<Result<Vec<PlannedAction>, BrixError>>()?;
+        Ok(actions)
     }
 
     fn name(&self) -> String {
@@ -117,23 +440,143 @@ where
     }
 }
 
+/// A single action that a command would take, as reported by [Command::plan].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlannedAction {
+    /// The file doesn't exist yet and would be created.
+    Create(PathBuf),
+    /// The file exists and would be overwritten.
+    Overwrite(PathBuf),
+    /// The file exists and would be left alone.
+    Skip(PathBuf),
+    /// The file exists and the user would be asked whether to overwrite it.
+    Ask(PathBuf),
+    /// The file exists and would be moved to `<name>.orig` before being overwritten.
+    Backup(PathBuf),
+    /// The file exists and the update would be merged into it.
+    Merge(PathBuf),
+    /// The source file is left out and wouldn't be written.
+    Ignore(PathBuf),
+    /// The directory would be created.
+    CreateDir(PathBuf),
+    /// The file would be edited with the number of times the search matched,
+    /// or `None` if the file doesn't exist yet.
+    Edit {
+        path: PathBuf,
+        search: String,
+        matches: Option<usize>,
+    },
+    /// Content would be injected into the file at the anchor, with whether the anchor matches,
+    /// or `None` if the file doesn't exist yet.
+    Inject {
+        path: PathBuf,
+        anchor: String,
+        matches: Option<bool>,
+    },
+    /// The file or directory would be removed.
+    Remove(PathBuf),
+    /// The line would be run in a shell.
+    Exec(String),
+    /// The plugin would be run.
+    Plugin(PathBuf),
+}
+
+impl Display for PlannedAction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            PlannedAction::Create(path) => write!(f, "create '{}'", path.display()),
+            PlannedAction::Overwrite(path) => write!(f, "overwrite '{}'", path.display()),
+            PlannedAction::Skip(path) => write!(f, "skip '{}', already exists", path.display()),
+            PlannedAction::Ask(path) => write!(f, "ask to overwrite '{}'", path.display()),
+            PlannedAction::Backup(path) => write!(
+                f,
+                "back up '{}' to '{}' and overwrite it",
+                path.display(),
+                backup_path(path).display()
+            ),
+            PlannedAction::Merge(path) => write!(f, "merge update into '{}'", path.display()),
+            PlannedAction::Ignore(path) => write!(f, "leave out '{}', ignored", path.display()),
+            PlannedAction::CreateDir(path) => write!(f, "create directory '{}'", path.display()),
+            PlannedAction::Edit {
+                path,
+                search,
+                matches: Some(matches),
+            } => write!(
+                f,
+                "replace {} match(es) of '{}' in '{}'",
+                matches,
+                search,
+                path.display()
+            ),
+            PlannedAction::Edit {
+                path,
+                search,
+                matches: None,
+            } => write!(
+                f,
+                "replace matches of '{}' in '{}', which doesn't exist yet",
+                search,
+                path.display()
+            ),
+            PlannedAction::Inject {
+                path,
+                anchor,
+                matches,
+            } => {
+                write!(f, "inject {} in '{}'", anchor, path.display())?;
+                match matches {
+                    Some(true) => Ok(()),
+                    Some(false) => write!(f, ", which doesn't match"),
+                    None => write!(f, ", which doesn't exist yet"),
+                }
+            }
+            PlannedAction::Remove(path) => write!(f, "remove '{}'", path.display()),
+            PlannedAction::Exec(line) => write!(f, "run `{}`", line),
+            PlannedAction::Plugin(path) => write!(f, "run plugin '{}'", path.display()),
+        }
+    }
+}
+
 /// Trait for command parameters that stem from `OverwritableComman`.
 pub trait OverwritableParams {
-    fn source(&self) -> PathBuf;
+    /// The file or directory that's written from, if the command has one.
+    fn source(&self) -> Option<PathBuf>;
     fn destination(&self) -> PathBuf;
-    fn overwrite(&self) -> Option<bool>;
+    fn overwrite(&self) -> Option<OverwritePolicy>;
 }
 
-/// A complete struct containing all possible fields in a command.
-/// All values are in their preferred type and not just strings.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ProcessedCommandParams {
-    pub source: Option<PathBuf>,
-    pub destination: Option<PathBuf>,
-    pub overwrite: Option<bool>,
-    pub search: Option<String>,
-    pub replace: Option<String>,
-    pub commands: Option<Vec<String>>,
-    pub stdout: Option<bool>,
-    pub context: Option<HashMap<String, String>>,
+/// The params of a single command in a config after templating, which every command
+/// deserializes into its own params type with [parse](CommandParams::parse).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CommandParams(Value);
+
+impl CommandParams {
+    pub fn new(params: Value) -> Self {
+        Self(params)
+    }
+
+    /// Deserializes the params into the params type of a command and validates them.
+    pub fn parse<T>(self) -> Result<T, BrixError>
+    where
+        T: DeserializeOwned + Validate,
+    {
+        let params: T = serde_json::from_value(self.0).map_err(|err| BrixError {
+            kind: Some(BrixErrorKind::Validation),
+            message: err.to_string(),
+        })?;
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// The params as they were templated, for commands that pass them on as is.
+    pub fn into_value(self) -> Value {
+        self.0
+    }
+}
+
+impl From<Value> for CommandParams {
+    fn from(params: Value) -> Self {
+        Self::new(params)
+    }
 }
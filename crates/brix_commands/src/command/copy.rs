@@ -5,62 +5,72 @@
 
 //! Contains [CopyCommand].
 
-extern crate fs_extra;
-
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use dialoguer::console::Term;
-use log::debug;
-use validator::{Validate, ValidationErrors};
+use globset::GlobSet;
+use log::{debug, info};
+use serde::Deserialize;
+use validator::Validate;
 
-use crate::command::{OverwritableCommand, OverwritableParams, ProcessedCommandParams};
+use crate::command::{CommandParams, OverwritableCommand, OverwritableParams};
 use crate::dir;
-use fs_extra::dir::{copy, CopyOptions};
-
+use crate::glob::{is_match, pattern_set, patterns};
+use brix_cli::OverwritePolicy;
 use brix_common::AppContext;
 use brix_errors::BrixError;
 
 #[cfg(test)]
 mod tests {
     mod from;
+    mod run;
 }
 
+/// Lists patterns to leave out of a copied directory, one per line.
+pub const IGNORE_FILE: &str = ".brixignore";
+
 #[derive(Debug)]
 pub struct CopyParams {
     source: PathBuf,
     destination: PathBuf,
-    overwrite: Option<bool>,
+    overwrite: Option<OverwritePolicy>,
+    include: Vec<String>,
+    exclude: Vec<String>,
 }
 
 impl PartialEq for CopyParams {
     fn eq(&self, other: &Self) -> bool {
         return self.source == other.source
             && self.destination == other.destination
-            && self.overwrite == other.overwrite;
+            && self.overwrite == other.overwrite
+            && self.include == other.include
+            && self.exclude == other.exclude;
     }
 }
 
 impl OverwritableParams for CopyParams {
-    fn source(&self) -> PathBuf {
-        self.source.clone()
+    fn source(&self) -> Option<PathBuf> {
+        Some(self.source.clone())
     }
 
     fn destination(&self) -> PathBuf {
         self.destination.clone()
     }
 
-    fn overwrite(&self) -> Option<bool> {
+    fn overwrite(&self) -> Option<OverwritePolicy> {
         self.overwrite
     }
 }
 
-#[derive(Debug, Validate)]
+#[derive(Debug, Deserialize, Validate)]
 struct Params {
     #[validate(required)]
     source: Option<PathBuf>,
     #[validate(required)]
     destination: Option<PathBuf>,
-    overwrite: Option<bool>,
+    overwrite: Option<OverwritePolicy>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
 }
 
 /// The Brix copy command
@@ -76,6 +86,63 @@ impl CopyCommand {
     }
 }
 
+impl CopyCommand {
+    /// The files to copy out of the source directory, relative to it. Only the files matching
+    /// an `include` pattern are copied, if there are any, leaving out the ones matching an
+    /// `exclude` pattern or a pattern in the `.brixignore` file.
+    /// Also returns the files that are left out.
+    fn files(
+        &self,
+        params: &CopyParams,
+        ctx: &AppContext,
+    ) -> Result<(Vec<PathBuf>, Vec<PathBuf>), BrixError> {
+        let all: Vec<PathBuf> = ctx
+            .fs
+            .walk(&params.source)?
+            .iter()
+            .map(|file| file.strip_prefix(&params.source).unwrap().to_path_buf())
+            .filter(|file| file != Path::new(IGNORE_FILE))
+            .collect();
+
+        let included = match params.include.is_empty() {
+            true => all.clone(),
+            false => {
+                let include = patterns(&params.include, &all, &params.source)?;
+                all.iter()
+                    .filter(|file| is_match(&include, file))
+                    .cloned()
+                    .collect()
+            }
+        };
+
+        let exclude = patterns(&params.exclude, &included, &params.source)?;
+        let ignore_file = params.source.join(IGNORE_FILE);
+        let ignored = match ctx.fs.is_file(&ignore_file) {
+            true => pattern_set(&ignore_patterns(&ctx.fs.read_to_string(&ignore_file)?))?,
+            false => GlobSet::empty(),
+        };
+        let files: Vec<PathBuf> = included
+            .into_iter()
+            .filter(|file| !is_match(&exclude, file) && !is_match(&ignored, file))
+            .collect();
+        let left_out = all
+            .into_iter()
+            .filter(|file| !files.contains(file))
+            .collect();
+        Ok((files, left_out))
+    }
+}
+
+/// The patterns of a `.brixignore` file, skipping empty lines and `#` comments.
+fn ignore_patterns(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
 impl OverwritableCommand for CopyCommand {
     type Params = CopyParams;
 
@@ -83,22 +150,58 @@ impl OverwritableCommand for CopyCommand {
         self.term.clone()
     }
 
-    fn from(&self, pcp: ProcessedCommandParams) -> Result<CopyParams, ValidationErrors> {
-        let cp = Params {
-            source: pcp.source,
-            destination: pcp.destination,
-            overwrite: pcp.overwrite,
-        };
-        cp.validate()?;
+    fn from(&self, params: CommandParams) -> Result<CopyParams, BrixError> {
+        let cp: Params = params.parse()?;
         Ok(Self::Params {
             source: cp.source.unwrap(),
             destination: cp.destination.unwrap(),
             overwrite: cp.overwrite,
+            include: cp.include.unwrap_or_default(),
+            exclude: cp.exclude.unwrap_or_default(),
         })
     }
 
+    fn destinations(
+        &self,
+        params: &CopyParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PathBuf>, BrixError> {
+        let dest = dir!(ctx.config.workdir, &params.destination);
+        if !ctx.fs.is_dir(&params.source) {
+            if !params.include.is_empty() || !params.exclude.is_empty() {
+                return Err(BrixError::with(&format!(
+                    "include and exclude need the source '{}' to be a directory",
+                    params.source.display()
+                )));
+            }
+            return Ok(vec![dest]);
+        }
+
+        // The contents of the directory are copied into the destination
+        let destinations = self
+            .files(params, ctx)?
+            .0
+            .iter()
+            .map(|file| dest.join(file))
+            .collect();
+        Ok(destinations)
+    }
+
+    fn ignored(&self, params: &CopyParams, ctx: &AppContext) -> Result<Vec<PathBuf>, BrixError> {
+        if !ctx.fs.is_dir(&params.source) {
+            return Ok(vec![]);
+        }
+        let ignored = self
+            .files(params, ctx)?
+            .1
+            .iter()
+            .map(|file| params.source.join(file))
+            .collect();
+        Ok(ignored)
+    }
+
     fn write_impl(&self, params: CopyParams, ctx: &AppContext) -> Result<(), BrixError> {
-        let dest = dir!(ctx.config.workdir, params.destination);
+        let dest = dir!(ctx.config.workdir, &params.destination);
 
         debug!(
             "copying '{}' to '{}'",
@@ -106,13 +209,24 @@ impl OverwritableCommand for CopyCommand {
             dest.display(),
         );
 
-        let options = CopyOptions {
-            overwrite: params.overwrite.unwrap_or(false),
-            content_only: true,
-            ..CopyOptions::new()
-        };
-
-        copy(params.source, dest, &options)?;
+        // Whether to overwrite has already been decided by the policy
+        if !ctx.fs.is_dir(&params.source) {
+            return Ok(ctx.fs.copy(&params.source, &dest)?);
+        }
+        let (files, left_out) = self.files(&params, ctx)?;
+        info!(
+            "copying {} of {} files in '{}'",
+            files.len(),
+            files.len() + left_out.len(),
+            params.source.display()
+        );
+        for file in files {
+            let target = dest.join(&file);
+            if let Some(parent) = target.parent() {
+                ctx.fs.create_dir_all(parent)?;
+            }
+            ctx.fs.copy(&params.source.join(&file), &target)?;
+        }
         Ok(())
     }
 
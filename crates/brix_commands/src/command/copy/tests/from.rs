@@ -5,34 +5,31 @@
 
 #[cfg(test)]
 mod params {
-    use std::path::PathBuf;
+    use serde_json::json;
 
     use dialoguer::console::Term;
 
     use crate::command::copy::{CopyParams, OverwritableCommand};
-    use crate::{CopyCommand, ProcessedCommandParams};
+    use crate::{CommandParams, CopyCommand};
+    use brix_errors::BrixErrorKind;
 
     #[test]
     fn valid() {
         let command = CopyCommand {
             term: Term::stdout(),
         };
-        let params = ProcessedCommandParams {
-            source: Option::Some(PathBuf::new()),
-            destination: Option::Some(PathBuf::new()),
-            overwrite: None,
-            search: None,
-            replace: None,
-            commands: None,
-            stdout: None,
-            context: None,
-        };
+        let params = CommandParams::from(json!({
+            "source": "",
+            "destination": "",
+        }));
         assert_eq!(
             command.from(params).unwrap(),
             CopyParams {
                 source: Default::default(),
                 destination: Default::default(),
-                overwrite: None
+                overwrite: None,
+                include: vec![],
+                exclude: vec![],
             }
         )
     }
@@ -43,25 +40,10 @@ mod params {
             term: Term::stdout(),
         };
 
-        // TODO: macros for processed command params since commands are changing
-        let params = ProcessedCommandParams {
-            source: None,
-            destination: None,
-            overwrite: None,
-            search: None,
-            replace: None,
-            commands: None,
-            stdout: None,
-            context: None,
-        };
+        let params = CommandParams::from(json!({}));
 
         let error = command.from(params).err().unwrap();
-        let errors = error.into_errors();
-        let keys = errors.keys();
-        let mut vec = keys.collect::<Vec<_>>();
-
-        vec.sort();
-
-        assert_eq!(vec, [&"destination", &"source"]);
+        assert_eq!(error.kind.unwrap(), BrixErrorKind::Validation);
+        assert!(error.message.contains("source"));
     }
 }
@@ -0,0 +1,114 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::command::{Command, PlannedAction};
+use crate::{CommandParams, CopyCommand};
+use brix_common::{AppContext, MemoryFilesystem};
+use brix_errors::BrixError;
+use brix_processor::ProcessorCore;
+
+const SOURCE: &str = "src/command/copy/shared";
+
+/// Copies the shared directory in memory, returning the copied files relative to the destination.
+fn copy(include: Value, exclude: Value) -> Result<Vec<PathBuf>, BrixError> {
+    let config = brix_cli::Config::default();
+    let fs = Rc::new(MemoryFilesystem::over_disk());
+    let context = AppContext::new(ProcessorCore::new(), &config).with_filesystem(fs.clone());
+
+    let params = CommandParams::from(json!({
+        "source": SOURCE,
+        "destination": "out",
+        "include": include,
+        "exclude": exclude,
+    }));
+    CopyCommand::new().run(params, &context)?;
+
+    let out = config.workdir.join("out");
+    Ok(fs
+        .files()
+        .keys()
+        .map(|path| path.strip_prefix(&out).unwrap().to_path_buf())
+        .collect())
+}
+
+#[test]
+fn everything() {
+    // The .brixignore file leaves out the logs and isn't copied itself
+    assert_eq!(
+        copy(Value::Null, Value::Null).unwrap(),
+        vec![
+            PathBuf::from("README.md"),
+            PathBuf::from("build/Main.class"),
+            PathBuf::from("build.gradle.kts"),
+            PathBuf::from("settings.gradle.kts"),
+            PathBuf::from("src/Main.kt"),
+        ]
+    );
+}
+
+#[test]
+fn include_and_exclude() {
+    assert_eq!(
+        copy(json!(["*.gradle.kts"]), Value::Null).unwrap(),
+        vec![
+            PathBuf::from("build.gradle.kts"),
+            PathBuf::from("settings.gradle.kts"),
+        ]
+    );
+    assert_eq!(
+        copy(Value::Null, json!(["build/", "*.md"])).unwrap(),
+        vec![
+            PathBuf::from("build.gradle.kts"),
+            PathBuf::from("settings.gradle.kts"),
+            PathBuf::from("src/Main.kt"),
+        ]
+    );
+}
+
+#[test]
+fn unmatched_pattern() {
+    let error = copy(json!(["*.gradle"]), Value::Null).unwrap_err();
+    assert!(error
+        .message
+        .contains("'*.gradle' does not match any files"));
+
+    // Only what's included can be excluded
+    let error = copy(json!(["src/"]), json!(["build/"])).unwrap_err();
+    assert!(error.message.contains("'build/'"));
+}
+
+#[test]
+fn plan() {
+    let config = brix_cli::Config::default();
+    let context = AppContext::new(ProcessorCore::new(), &config)
+        .with_filesystem(Rc::new(MemoryFilesystem::over_disk()));
+
+    let params = CommandParams::from(json!({
+        "source": SOURCE,
+        "destination": "out",
+        "exclude": ["build/", "*.md"],
+    }));
+    let ignored: Vec<PlannedAction> = CopyCommand::new()
+        .plan(params, &context)
+        .unwrap()
+        .into_iter()
+        .filter(|action| matches!(action, PlannedAction::Ignore(_)))
+        .collect();
+
+    // What's left out is listed by its source path
+    let source = PathBuf::from(SOURCE);
+    assert_eq!(
+        ignored,
+        vec![
+            PlannedAction::Ignore(source.join("README.md")),
+            PlannedAction::Ignore(source.join("build/Main.class")),
+            PlannedAction::Ignore(source.join("src/debug.log")),
+        ]
+    );
+}
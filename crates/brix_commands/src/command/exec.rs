@@ -6,10 +6,11 @@
 //! Contains [ExecCommand].
 
 use execute::{shell, Execute};
+use serde::Deserialize;
 use std::process::Stdio;
 use validator::Validate;
 
-use crate::command::{Command, ProcessedCommandParams};
+use crate::command::{Command, CommandParams, PlannedAction};
 use brix_common::AppContext;
 use brix_errors::BrixError;
 
@@ -28,7 +29,7 @@ impl PartialEq for ExecParams {
     }
 }
 
-#[derive(Debug, Validate)]
+#[derive(Debug, Deserialize, Validate)]
 struct Params {
     #[validate(required, length(min = 1))]
     commands: Option<Vec<String>>,
@@ -45,12 +46,8 @@ impl ExecCommand {
 }
 
 impl Command for ExecCommand {
-    fn run(&self, pcp: ProcessedCommandParams, _app_context: &AppContext) -> Result<(), BrixError> {
-        let cp = Params {
-            commands: pcp.commands,
-            stdout: pcp.stdout,
-        };
-        cp.validate()?;
+    fn run(&self, params: CommandParams, _app_context: &AppContext) -> Result<(), BrixError> {
+        let cp: Params = params.parse()?;
 
         let commands = cp.commands.unwrap();
         let use_stdout = cp.stdout.unwrap_or(true);
@@ -82,6 +79,21 @@ impl Command for ExecCommand {
         Ok(())
     }
 
+    fn plan(
+        &self,
+        params: CommandParams,
+        _app_context: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        let cp: Params = params.parse()?;
+
+        Ok(cp
+            .commands
+            .unwrap()
+            .into_iter()
+            .map(PlannedAction::Exec)
+            .collect())
+    }
+
     fn name(&self) -> String {
         String::from("exec")
     }
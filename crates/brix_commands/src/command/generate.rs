@@ -0,0 +1,101 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [GenerateCommand].
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use validator::Validate;
+
+use crate::command::{Command, CommandParams, PlannedAction};
+use brix_common::AppContext;
+use brix_errors::BrixError;
+
+#[cfg(test)]
+mod tests {
+    mod run;
+}
+
+/// Loads and runs the commands of another config.
+/// Implemented outside of this crate since commands don't know how configs are found or parsed.
+pub trait Generator {
+    fn generate(
+        &self,
+        language: &str,
+        config: &str,
+        context: HashMap<String, Value>,
+        app_context: &AppContext,
+    ) -> Result<(), BrixError>;
+
+    /// Describes what [generate](Generator::generate) would do, see [Command::plan].
+    fn plan(
+        &self,
+        language: &str,
+        config: &str,
+        context: HashMap<String, Value>,
+        app_context: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError>;
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Params {
+    language: Option<String>,
+    #[validate(required, length(min = 1))]
+    config: Option<String>,
+    /// Only the context declared on the command is passed on, since the whole context
+    /// of the current config would override the context and defaults of the other one.
+    local_context: Option<HashMap<String, Value>>,
+}
+
+/// The Brix generate command
+pub struct GenerateCommand {
+    generator: Box<dyn Generator>,
+}
+
+impl GenerateCommand {
+    pub fn new(generator: Box<dyn Generator>) -> Self {
+        Self { generator }
+    }
+}
+
+impl GenerateCommand {
+    /// Validates the params and returns the language, config name and context to generate with.
+    fn args(
+        &self,
+        params: CommandParams,
+        ctx: &AppContext,
+    ) -> Result<(String, String, HashMap<String, Value>), BrixError> {
+        let cp: Params = params.parse()?;
+
+        // Defaults to the language of the current config
+        let language = cp.language.unwrap_or_else(|| ctx.config.language.clone());
+        let mut context = cp.local_context.unwrap_or_default();
+        // The child config sets its own language
+        context.remove("language");
+
+        Ok((language, cp.config.unwrap(), context))
+    }
+}
+
+impl Command for GenerateCommand {
+    fn run(&self, params: CommandParams, ctx: &AppContext) -> Result<(), BrixError> {
+        let (language, config, context) = self.args(params, ctx)?;
+        self.generator.generate(&language, &config, context, ctx)
+    }
+
+    fn plan(
+        &self,
+        params: CommandParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        let (language, config, context) = self.args(params, ctx)?;
+        self.generator.plan(&language, &config, context, ctx)
+    }
+
+    fn name(&self) -> String {
+        String::from("generate")
+    }
+}
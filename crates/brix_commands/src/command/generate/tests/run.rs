@@ -0,0 +1,116 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::command::Command;
+use crate::{CommandParams, GenerateCommand, Generator, PlannedAction};
+use brix_common::AppContext;
+use brix_errors::{BrixError, BrixErrorKind};
+use brix_processor::ProcessorCore;
+
+type Calls = Rc<RefCell<Vec<(String, String, HashMap<String, Value>)>>>;
+
+struct RecordingGenerator {
+    calls: Calls,
+}
+
+impl Generator for RecordingGenerator {
+    fn generate(
+        &self,
+        language: &str,
+        config: &str,
+        context: HashMap<String, Value>,
+        _app_context: &AppContext,
+    ) -> Result<(), BrixError> {
+        self.calls
+            .borrow_mut()
+            .push((language.to_string(), config.to_string(), context));
+        Ok(())
+    }
+
+    fn plan(
+        &self,
+        _language: &str,
+        _config: &str,
+        _context: HashMap<String, Value>,
+        _app_context: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        Ok(vec![])
+    }
+}
+
+macro_rules! run {
+    ($language:expr, $config:expr, $context:expr) => {{
+        let calls = Calls::default();
+        let processor = ProcessorCore::new();
+        let mut config = brix_cli::Config::default();
+        config.language = String::from("java");
+        let context = AppContext::new(processor, &config);
+        let command = GenerateCommand::new(Box::new(RecordingGenerator {
+            calls: calls.clone(),
+        }));
+        let (language, config, context_param): (
+            Option<String>,
+            Option<String>,
+            Option<HashMap<String, Value>>,
+        ) = ($language, $config, $context);
+        let result = command.run(
+            CommandParams::from(json!({
+                "language": language,
+                "config": config,
+                "context": {"project": "parent"},
+                "local_context": context_param,
+            })),
+            &context,
+        );
+        (result, calls.take())
+    }};
+}
+
+#[test]
+fn nothing() {
+    let (result, calls) = run!(None, None, None);
+    assert_eq!(result.unwrap_err().kind.unwrap(), BrixErrorKind::Validation);
+    assert!(calls.is_empty());
+}
+
+#[test]
+fn default_language() {
+    let (result, calls) = run!(None, Some(String::from("controller")), None);
+    result.unwrap();
+    assert_eq!(
+        calls,
+        vec![(
+            String::from("java"),
+            String::from("controller"),
+            HashMap::new()
+        )]
+    );
+}
+
+#[test]
+fn context() {
+    let mut context = HashMap::new();
+    context.insert(String::from("language"), json!("java"));
+    context.insert(String::from("module"), json!("user"));
+
+    let (result, calls) = run!(
+        Some(String::from("kotlin")),
+        Some(String::from("dto")),
+        Some(context)
+    );
+    result.unwrap();
+
+    let mut expected = HashMap::new();
+    expected.insert(String::from("module"), json!("user"));
+    assert_eq!(
+        calls,
+        vec![(String::from("kotlin"), String::from("dto"), expected)]
+    );
+}
@@ -0,0 +1,259 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [InjectCommand]
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+
+use fancy_regex::Regex;
+use log::info;
+use serde::Deserialize;
+use serde_json::Value;
+use validator::Validate;
+
+use crate::command::{Command, CommandParams, PlannedAction};
+use crate::dir;
+use brix_common::AppContext;
+use brix_errors::{BrixError, BrixErrorKind};
+
+#[cfg(test)]
+mod tests {
+    mod invalid;
+    mod run;
+}
+
+/// Where the content is injected, always on lines of its own.
+#[derive(Debug)]
+enum Anchor {
+    /// Before the line with the first match.
+    Before(Regex),
+    /// After the line with the first match.
+    After(Regex),
+    /// Before the line with the last match.
+    BeforeLast(Regex),
+    Start,
+    End,
+}
+
+impl Display for Anchor {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Anchor::Before(re) => write!(f, "before '{}'", pattern(re)),
+            Anchor::After(re) => write!(f, "after '{}'", pattern(re)),
+            Anchor::BeforeLast(re) => write!(f, "before the last '{}'", pattern(re)),
+            Anchor::Start => write!(f, "at the start"),
+            Anchor::End => write!(f, "at the end"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Position {
+    Start,
+    End,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Params {
+    #[validate(required)]
+    destination: Option<PathBuf>,
+    content: Option<String>,
+    source: Option<PathBuf>,
+    context: Option<HashMap<String, Value>>,
+    before: Option<String>,
+    after: Option<String>,
+    before_last: Option<String>,
+    at: Option<Position>,
+    skip_if: Option<String>,
+}
+
+/// The Brix inject command
+#[derive(Default)]
+pub struct InjectCommand {}
+
+impl InjectCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// The anchor of the params, of which there has to be exactly one.
+    fn anchor(&self, cp: &Params) -> Result<Anchor, BrixError> {
+        let mut anchors = Vec::new();
+        if let Some(before) = &cp.before {
+            anchors.push(Anchor::Before(regex(before)?));
+        }
+        if let Some(after) = &cp.after {
+            anchors.push(Anchor::After(regex(after)?));
+        }
+        if let Some(before_last) = &cp.before_last {
+            anchors.push(Anchor::BeforeLast(regex(before_last)?));
+        }
+        match cp.at {
+            Some(Position::Start) => anchors.push(Anchor::Start),
+            Some(Position::End) => anchors.push(Anchor::End),
+            None => {}
+        }
+
+        match anchors.len() {
+            1 => Ok(anchors.remove(0)),
+            _ => Err(invalid(
+                "exactly one of 'before', 'after', 'before_last' or 'at' is required",
+            )),
+        }
+    }
+
+    /// The inline content, or the source template rendered with the context.
+    fn content(&self, cp: &Params, ctx: &AppContext) -> Result<String, BrixError> {
+        match (&cp.content, &cp.source) {
+            (Some(content), None) => Ok(content.clone()),
+            (None, Some(source)) => {
+                let source = dir!(ctx.config.workdir, source);
+                let contents = ctx.fs.read_to_string(&source)?;
+                let context = cp.context.clone().unwrap_or_default();
+                ctx.processor
+                    .process(contents, brix_processor::create_context(context))
+            }
+            _ => Err(invalid("either 'content' or 'source' is required")),
+        }
+    }
+}
+
+/// The pattern of a regex built by [regex].
+fn pattern(re: &Regex) -> &str {
+    re.as_str().trim_start_matches("(?m)")
+}
+
+/// Builds the regex in multi-line mode, since `^` and `$` are most useful on lines here.
+fn regex(pattern: &str) -> Result<Regex, BrixError> {
+    Ok(Regex::new(&format!("(?m){}", pattern))?)
+}
+
+fn invalid(message: &str) -> BrixError {
+    BrixError {
+        kind: Some(BrixErrorKind::Validation),
+        message: String::from(message),
+    }
+}
+
+/// Inserts the content on its own lines at the anchor, or returns `None` if the anchor doesn't match.
+fn inject(data: &str, content: &str, anchor: &Anchor) -> Result<Option<String>, BrixError> {
+    let index = match anchor {
+        Anchor::Start => 0,
+        Anchor::End => data.len(),
+        Anchor::Before(re) => match re.find(data)? {
+            Some(m) => line_start(data, m.start()),
+            None => return Ok(None),
+        },
+        Anchor::BeforeLast(re) => match re.find_iter(data).last().transpose()? {
+            Some(m) => line_start(data, m.start()),
+            None => return Ok(None),
+        },
+        Anchor::After(re) => match re.find(data)? {
+            Some(m) => line_end(data, m.end()),
+            None => return Ok(None),
+        },
+    };
+
+    let mut result = String::from(&data[..index]);
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+    result.push_str(content);
+    if !content.ends_with('\n') {
+        result.push('\n');
+    }
+    result.push_str(&data[index..]);
+    Ok(Some(result))
+}
+
+fn line_start(data: &str, index: usize) -> usize {
+    data[..index].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// The start of the line after the one the index is on, unless the index is already at one.
+fn line_end(data: &str, index: usize) -> usize {
+    if index > 0 && data[..index].ends_with('\n') {
+        return index;
+    }
+    data[index..]
+        .find('\n')
+        .map_or(data.len(), |i| index + i + 1)
+}
+
+impl Command for InjectCommand {
+    fn run(&self, params: CommandParams, ctx: &AppContext) -> Result<(), BrixError> {
+        let cp: Params = params.parse()?;
+        let anchor = self.anchor(&cp)?;
+        let content = self.content(&cp, ctx)?;
+
+        let dest = dir!(ctx.config.workdir, cp.destination.as_ref().unwrap());
+        let data = ctx.fs.read_to_string(&dest).map_err(|err| {
+            BrixError::with(&format!(
+                "unable to read file '{}': {}",
+                dest.display(),
+                err
+            ))
+        })?;
+
+        if let Some(skip_if) = &cp.skip_if {
+            if regex(skip_if)?.is_match(&data)? {
+                info!("skipping: '{}', '{}' matches", dest.display(), skip_if);
+                return Ok(());
+            }
+        }
+
+        let result = inject(&data, &content, &anchor)?.ok_or_else(|| {
+            BrixError::with(&format!(
+                "unable to inject {} in '{}', it doesn't match",
+                anchor,
+                dest.display()
+            ))
+        })?;
+
+        ctx.journal.record(&dest)?;
+        info!("injecting {} in '{}'", anchor, dest.display());
+        ctx.fs.write(&dest, result.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn plan(
+        &self,
+        params: CommandParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        let cp: Params = params.parse()?;
+        let anchor = self.anchor(&cp)?;
+        let content = self.content(&cp, ctx)?;
+        let dest = dir!(ctx.config.workdir, cp.destination.as_ref().unwrap());
+
+        // The file might still be created by an earlier command
+        let matches = match ctx.fs.exists(&dest) {
+            true => {
+                let data = ctx.fs.read_to_string(&dest)?;
+                if let Some(skip_if) = &cp.skip_if {
+                    if regex(skip_if)?.is_match(&data)? {
+                        return Ok(vec![]);
+                    }
+                }
+                Some(inject(&data, &content, &anchor)?.is_some())
+            }
+            false => None,
+        };
+
+        Ok(vec![PlannedAction::Inject {
+            path: dest,
+            anchor: anchor.to_string(),
+            matches,
+        }])
+    }
+
+    fn name(&self) -> String {
+        String::from("inject")
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+
+use crate::command::Command;
+use crate::{CommandParams, InjectCommand};
+use brix_common::AppContext;
+use brix_errors::BrixErrorKind;
+use brix_processor::ProcessorCore;
+
+macro_rules! run {
+    ($args:expr) => {{
+        let processor = ProcessorCore::new();
+        let config = brix_cli::Config::default();
+        let command = InjectCommand::new();
+        let context = AppContext::new(processor, &config);
+        // Ensure it is a validation error
+        assert_eq!(
+            command.run($args, &context).unwrap_err().kind.unwrap(),
+            BrixErrorKind::Validation
+        );
+    }};
+}
+
+#[test]
+fn nothing() {
+    run!(CommandParams::from(json!({})))
+}
+
+#[test]
+fn no_anchor() {
+    run!(CommandParams::from(json!({
+        "destination": "lib.rs",
+        "content": "mod foo;",
+    })))
+}
+
+#[test]
+fn two_anchors() {
+    run!(CommandParams::from(json!({
+        "destination": "lib.rs",
+        "content": "mod foo;",
+        "after": "^mod",
+        "at": "end",
+    })))
+}
+
+#[test]
+fn no_content() {
+    run!(CommandParams::from(json!({
+        "destination": "lib.rs",
+        "at": "end",
+    })))
+}
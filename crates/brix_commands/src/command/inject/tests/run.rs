@@ -0,0 +1,81 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+
+use crate::command::testing::Workspace;
+use crate::command::Command;
+use crate::{CommandParams, InjectCommand, PlannedAction};
+use brix_common::AppContext;
+use brix_errors::BrixError;
+use brix_processor::ProcessorCore;
+
+const LIB: &str = "use std::fmt;\n\nmod bar;\nmod baz;\n\nfn main() {\n    run();\n}\n";
+
+/// Injects into a copy of `LIB` in memory and returns the result.
+fn inject(params: Value) -> Result<String, BrixError> {
+    let workspace = Workspace::new(&[("lib.rs", LIB)]);
+
+    let mut params = params;
+    params["destination"] = json!("lib.rs");
+    InjectCommand::new().run(CommandParams::from(params), &workspace.context())?;
+    Ok(workspace.read("lib.rs"))
+}
+
+#[test]
+fn anchors() {
+    assert_eq!(
+        inject(json!({"content": "mod foo;", "after": "^mod baz;"})).unwrap(),
+        "use std::fmt;\n\nmod bar;\nmod baz;\nmod foo;\n\nfn main() {\n    run();\n}\n"
+    );
+    assert_eq!(
+        inject(json!({"content": "mod foo;", "before": "^mod"})).unwrap(),
+        "use std::fmt;\n\nmod foo;\nmod bar;\nmod baz;\n\nfn main() {\n    run();\n}\n"
+    );
+    assert_eq!(
+        inject(json!({"content": "    stop();\n", "before_last": "\\}"})).unwrap(),
+        "use std::fmt;\n\nmod bar;\nmod baz;\n\nfn main() {\n    run();\n    stop();\n}\n"
+    );
+    assert_eq!(
+        inject(json!({"content": "// Generated", "at": "start"})).unwrap(),
+        format!("// Generated\n{}", LIB)
+    );
+    assert_eq!(
+        inject(json!({"content": "// End", "at": "end"})).unwrap(),
+        format!("{}// End\n", LIB)
+    );
+}
+
+#[test]
+fn skip_if() {
+    let params = json!({"content": "mod bar;", "after": "^mod baz;", "skip_if": "^mod bar;$"});
+    assert_eq!(inject(params).unwrap(), LIB);
+}
+
+#[test]
+fn no_match() {
+    let err = inject(json!({"content": "mod foo;", "after": "^struct"})).unwrap_err();
+    assert!(err.message.contains("after '^struct'"));
+}
+
+#[test]
+fn plan() {
+    let config = brix_cli::Config::default();
+    let context = AppContext::new(ProcessorCore::new(), &config);
+
+    let params = CommandParams::from(json!({
+        "destination": "missing.rs",
+        "content": "mod foo;",
+        "at": "end",
+    }));
+    assert_eq!(
+        InjectCommand::new().plan(params, &context).unwrap(),
+        vec![PlannedAction::Inject {
+            path: config.workdir.join("missing.rs"),
+            anchor: String::from("at the end"),
+            matches: None,
+        }]
+    );
+}
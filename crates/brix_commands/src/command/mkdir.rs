@@ -5,12 +5,12 @@
 
 //! Contains [MkdirCommand].
 
-use std::fs::create_dir_all;
+use serde::Deserialize;
 use std::path::PathBuf;
 use validator::Validate;
 
 use crate::{
-    command::{Command, ProcessedCommandParams},
+    command::{Command, CommandParams, PlannedAction},
     dir,
 };
 use brix_common::AppContext;
@@ -33,7 +33,7 @@ impl PartialEq for MkdirParams {
     }
 }
 
-#[derive(Debug, Validate)]
+#[derive(Debug, Deserialize, Validate)]
 struct Params {
     #[validate(required)]
     destination: Option<PathBuf>,
@@ -49,18 +49,30 @@ impl MkdirCommand {
 }
 
 impl Command for MkdirCommand {
-    fn run(&self, pcp: ProcessedCommandParams, ctx: &AppContext) -> Result<(), BrixError> {
-        let cp = Params {
-            destination: pcp.destination,
-        };
-        cp.validate()?;
+    fn run(&self, params: CommandParams, ctx: &AppContext) -> Result<(), BrixError> {
+        let cp: Params = params.parse()?;
 
         let dest = dir!(ctx.config.workdir, cp.destination.unwrap());
-        create_dir_all(dest)?;
+        ctx.journal.record(&dest)?;
+        ctx.fs.create_dir_all(&dest)?;
 
         Ok(())
     }
 
+    fn plan(
+        &self,
+        params: CommandParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        let cp: Params = params.parse()?;
+
+        let dest = dir!(ctx.config.workdir, cp.destination.unwrap());
+        if ctx.fs.is_dir(&dest) {
+            return Ok(vec![]);
+        }
+        Ok(vec![PlannedAction::CreateDir(dest)])
+    }
+
     fn name(&self) -> String {
         String::from("mkdir")
     }
@@ -3,10 +3,11 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
+use serde_json::json;
 use std::path::PathBuf;
 
 use crate::command::Command;
-use crate::{MkdirCommand, ProcessedCommandParams};
+use crate::{CommandParams, MkdirCommand};
 use brix_common::AppContext;
 use brix_processor::ProcessorCore;
 
@@ -15,23 +16,13 @@ macro_rules! do_test {
         let processor = ProcessorCore::new();
         let config = brix_cli::Config::default();
         let command = MkdirCommand::new();
-        let context = AppContext {
-            processor,
-            config: &config,
-        };
+        let context = AppContext::new(processor, &config);
 
         let path = PathBuf::from("src/command/mkdir/temp").join($destination);
 
-        let params = ProcessedCommandParams {
-            source: None,
-            destination: Some(path.clone()),
-            overwrite: None,
-            search: None,
-            replace: None,
-            commands: None,
-            stdout: None,
-            context: None,
-        };
+        let params = CommandParams::from(json!({
+            "destination": path.clone(),
+        }));
 
         command.run(params, &context).unwrap();
         let exists = path.exists();
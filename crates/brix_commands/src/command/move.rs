@@ -0,0 +1,148 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [MoveCommand]
+
+use std::path::PathBuf;
+
+use dialoguer::console::Term;
+use log::debug;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::command::{workdir_path, CommandParams, OverwritableCommand, OverwritableParams};
+use brix_cli::OverwritePolicy;
+use brix_common::AppContext;
+use brix_errors::BrixError;
+
+#[cfg(test)]
+mod tests {
+    mod run;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MoveParams {
+    from: PathBuf,
+    destination: PathBuf,
+    overwrite: Option<OverwritePolicy>,
+}
+
+impl OverwritableParams for MoveParams {
+    // The path being moved is in the working directory, so it's checked along with the destination
+    fn source(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn destination(&self) -> PathBuf {
+        self.destination.clone()
+    }
+
+    fn overwrite(&self) -> Option<OverwritePolicy> {
+        self.overwrite
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Params {
+    #[validate(required)]
+    from: Option<PathBuf>,
+    #[validate(required)]
+    destination: Option<PathBuf>,
+    overwrite: Option<OverwritePolicy>,
+}
+
+/// The Brix move command
+pub struct MoveCommand {
+    term: Term,
+}
+
+impl MoveCommand {
+    pub fn new() -> Self {
+        Self {
+            term: Term::stderr(),
+        }
+    }
+}
+
+impl Default for MoveCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OverwritableCommand for MoveCommand {
+    type Params = MoveParams;
+
+    fn term(&self) -> Term {
+        self.term.clone()
+    }
+
+    fn from(&self, params: CommandParams) -> Result<MoveParams, BrixError> {
+        let cp: Params = params.parse()?;
+        Ok(Self::Params {
+            from: cp.from.unwrap(),
+            destination: cp.destination.unwrap(),
+            overwrite: cp.overwrite,
+        })
+    }
+
+    fn destinations(
+        &self,
+        params: &MoveParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PathBuf>, BrixError> {
+        let from = workdir_path(&params.from, ctx)?;
+        let dest = workdir_path(&params.destination, ctx)?;
+        if dest.starts_with(&from) {
+            return Err(BrixError::with(&format!(
+                "unable to move '{}' into itself",
+                from.display()
+            )));
+        }
+        if !ctx.fs.is_dir(&from) || ctx.fs.is_symlink(&from) {
+            return Ok(vec![dest]);
+        }
+
+        // The files are moved one by one, so the destination can already have other files
+        Ok(ctx
+            .fs
+            .walk(&from)?
+            .iter()
+            .map(|file| dest.join(file.strip_prefix(&from).unwrap()))
+            .collect())
+    }
+
+    fn write_impl(&self, params: MoveParams, ctx: &AppContext) -> Result<(), BrixError> {
+        let from = workdir_path(&params.from, ctx)?;
+        let dest = workdir_path(&params.destination, ctx)?;
+        debug!("moving '{}' to '{}'", from.display(), dest.display());
+        // Checked here instead of with the destinations, since an earlier command might still create it
+        if !ctx.fs.exists(&from) && !ctx.fs.is_symlink(&from) {
+            return Err(BrixError::with(&format!(
+                "'{}' does not exist",
+                from.display()
+            )));
+        }
+
+        if !ctx.fs.is_dir(&from) || ctx.fs.is_symlink(&from) {
+            ctx.journal.record(&from)?;
+            return Ok(ctx.fs.rename(&from, &dest)?);
+        }
+        for file in ctx.fs.walk(&from)? {
+            let target = dest.join(file.strip_prefix(&from).unwrap());
+            if let Some(parent) = target.parent() {
+                ctx.fs.create_dir_all(parent)?;
+            }
+            ctx.journal.record(&file)?;
+            ctx.fs.rename(&file, &target)?;
+        }
+        ctx.fs.remove_dir_all(&from)?;
+        Ok(())
+    }
+
+    fn name_inner(&self) -> String {
+        String::from("move")
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+use std::path::Path;
+
+use crate::command::testing::Workspace;
+use crate::command::Command;
+use crate::{CommandParams, MoveCommand};
+use brix_common::Filesystem;
+
+fn workspace() -> Workspace {
+    Workspace::new(&[
+        ("app/src/placeholder/mod.rs", "mod"),
+        ("app/src/placeholder/api.rs", "api"),
+        ("app/src/users/db.rs", "db"),
+    ])
+}
+
+fn params(from: &str, destination: &str) -> CommandParams {
+    CommandParams::from(json!({
+        "from": from,
+        "destination": destination,
+        "overwrite": "always",
+    }))
+}
+
+#[test]
+fn file() {
+    let workspace = workspace();
+    MoveCommand::new()
+        .run(
+            params("app/src/users/db.rs", "app/db/users.rs"),
+            &workspace.context(),
+        )
+        .unwrap();
+
+    assert!(!workspace.fs.exists(&workspace.path("app/src/users/db.rs")));
+    assert_eq!(workspace.read("app/db/users.rs"), "db");
+}
+
+#[test]
+fn directory() {
+    let workspace = workspace();
+    let (context, fs) = (workspace.context(), &workspace.fs);
+    MoveCommand::new()
+        .run(params("app/src/placeholder", "app/src/users"), &context)
+        .unwrap();
+
+    let src = workspace.path("app/src");
+    assert!(!fs.exists(&src.join("placeholder")));
+    assert_eq!(
+        fs.walk(&src).unwrap(),
+        vec![
+            src.join("users/api.rs"),
+            src.join("users/db.rs"),
+            src.join("users/mod.rs"),
+        ]
+    );
+
+    context.journal.rollback().unwrap();
+    assert_eq!(
+        fs.walk(&src).unwrap(),
+        vec![
+            src.join("placeholder/api.rs"),
+            src.join("placeholder/mod.rs"),
+            src.join("users/db.rs"),
+        ]
+    );
+}
+
+#[test]
+fn invalid() {
+    let workspace = workspace();
+    let context = workspace.context();
+    let command = MoveCommand::new();
+
+    let err = command
+        .run(params("app/missing.rs", "app/found.rs"), &context)
+        .unwrap_err();
+    assert!(err.message.contains("does not exist"));
+
+    let err = command
+        .run(params("app/src", "app/src/nested"), &context)
+        .unwrap_err();
+    assert!(err.message.contains("into itself"));
+
+    let err = command
+        .run(params("app/src", "../src"), &context)
+        .unwrap_err();
+    assert!(err
+        .message
+        .contains("isn't inside of the working directory"));
+}
+
+#[test]
+fn link_outside_workdir() {
+    let workspace = workspace();
+    let (context, fs) = (workspace.context(), &workspace.fs);
+    let outside = Path::new("/brix-outside/secret.rs");
+    fs.write(outside, b"secret").unwrap();
+    fs.symlink(
+        Path::new("/brix-outside"),
+        &workspace.path("app/src/placeholder/shared"),
+    )
+    .unwrap();
+    let command = MoveCommand::new();
+
+    let err = command
+        .run(
+            params("app/src/placeholder/shared/secret.rs", "app/secret.rs"),
+            &context,
+        )
+        .unwrap_err();
+    assert!(err
+        .message
+        .contains("isn't inside of the working directory"));
+
+    // The link is moved, and what it points to stays where it is
+    command
+        .run(params("app/src/placeholder", "app/src/users"), &context)
+        .unwrap();
+    assert!(fs.is_symlink(&workspace.path("app/src/users/shared")));
+    assert_eq!(fs.read(outside).unwrap(), b"secret");
+}
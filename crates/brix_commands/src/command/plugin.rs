@@ -0,0 +1,207 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [PluginCommand], which runs an external `brix-<name>` executable
+//! for commands that aren't registered.
+
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+use std::thread;
+
+use colored::*;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::command::{Command, CommandParams, PlannedAction};
+use crate::dir;
+use brix_common::AppContext;
+use brix_errors::BrixError;
+
+#[cfg(test)]
+mod tests {
+    mod run;
+}
+
+/// What the plugin is sent on stdin.
+#[derive(Debug, Serialize)]
+struct PluginInput<'a> {
+    command: &'a str,
+    /// The templated params of the command, without its context.
+    params: Value,
+    /// The merged context of the command, including anything exported by earlier plugins.
+    context: HashMap<String, Value>,
+    workdir: &'a Path,
+}
+
+/// What the plugin prints to stdout, both fields are optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PluginOutput {
+    /// Every file the plugin created or changed, relative to the working directory.
+    files: Vec<PathBuf>,
+    /// Context to make available to the commands that run after the plugin.
+    context: HashMap<String, Value>,
+}
+
+/// Runs the executable `brix-<name>` with the params and context as JSON on stdin,
+/// and reads the files it touched and the context it exports as JSON from stdout.
+#[derive(Clone)]
+pub struct PluginCommand {
+    name: String,
+    path: PathBuf,
+}
+
+impl PluginCommand {
+    pub fn new(name: &str, path: PathBuf) -> Self {
+        Self {
+            name: name.to_string(),
+            path,
+        }
+    }
+
+    /// Finds the `brix-<name>` executable in the plugin directory or on the `PATH`, in that order.
+    pub fn find(name: &str, plugin_dir: Option<&Path>) -> Option<Self> {
+        let file_name = format!("brix-{}", name);
+        let path_dirs: Vec<PathBuf> = env::var_os("PATH")
+            .map(|paths| env::split_paths(&paths).collect())
+            .unwrap_or_default();
+
+        plugin_dir
+            .map(Path::to_path_buf)
+            .into_iter()
+            .chain(path_dirs)
+            .map(|dir| dir.join(&file_name))
+            .find(|path| is_executable(path))
+            .map(|path| Self::new(name, path))
+    }
+}
+
+/// Whether the path is a file that can be run, which on unix needs an executable bit.
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+impl Command for PluginCommand {
+    fn run(&self, params: CommandParams, app_context: &AppContext) -> Result<(), BrixError> {
+        let workdir = &app_context.config.workdir;
+        let mut params = params.into_value();
+        let mut context: HashMap<String, Value> = match params.as_object_mut() {
+            Some(object) => {
+                // Already part of the context
+                object.remove("local_context");
+                match object.remove("context") {
+                    Some(context) => serde_json::from_value(context)?,
+                    None => HashMap::new(),
+                }
+            }
+            None => HashMap::new(),
+        };
+        context.extend(app_context.exports.borrow().clone());
+
+        let input = serde_json::to_vec(&PluginInput {
+            command: &self.name,
+            params,
+            context,
+            workdir,
+        })?;
+
+        info!("[[ {} ]]", self.path.display().to_string().bold());
+        let mut child = Process::new(&self.path)
+            .current_dir(workdir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| {
+                BrixError::with(&format!(
+                    "unable to run plugin '{}': {}",
+                    self.path.display(),
+                    err
+                ))
+            })?;
+        // Written while the output is read, since the plugin could fill up the pipe of its stdout
+        // before reading all of its stdin
+        let mut stdin = child.stdin.take().unwrap();
+        let writer = thread::spawn(move || stdin.write_all(&input));
+        let output = child.wait_with_output()?;
+        let written = writer.join().unwrap_or_else(|_| {
+            Err(io::Error::new(
+                ErrorKind::Other,
+                "writing to the plugin panicked",
+            ))
+        });
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if !output.status.success() {
+            return Err(BrixError::with(&format!(
+                "plugin '{}' failed with {}\n{}",
+                self.path.display(),
+                output.status,
+                stderr.trim_end()
+            )));
+        }
+        match written {
+            // The plugin may exit without reading everything, which is up to it
+            Err(err) if err.kind() != ErrorKind::BrokenPipe => {
+                return Err(BrixError::with(&format!(
+                    "unable to write to plugin '{}': {}",
+                    self.path.display(),
+                    err
+                )))
+            }
+            _ => {}
+        }
+        if !stderr.is_empty() {
+            debug!("{}", stderr.trim_end());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let result: PluginOutput = match stdout.trim() {
+            "" => PluginOutput::default(),
+            stdout => serde_json::from_str(stdout).map_err(|err| {
+                BrixError::with(&format!(
+                    "plugin '{}' printed invalid JSON: {}",
+                    self.path.display(),
+                    err
+                ))
+            })?,
+        };
+
+        for file in result.files.iter() {
+            let path = dir!(workdir, file);
+            info!("touched: '{}'", path.display());
+            app_context.journal.record_touched(&path);
+        }
+        app_context.exports.borrow_mut().extend(result.context);
+
+        Ok(())
+    }
+
+    fn plan(
+        &self,
+        _params: CommandParams,
+        _app_context: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        Ok(vec![PlannedAction::Plugin(self.path.clone())])
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
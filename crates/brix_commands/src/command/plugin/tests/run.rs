@@ -0,0 +1,96 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::command::Command;
+use crate::{CommandParams, PluginCommand};
+use brix_common::journal::Change;
+use brix_common::AppContext;
+use brix_processor::ProcessorCore;
+
+const PLUGIN_DIR: &str = "src/command/plugin/bin";
+
+#[test]
+fn find() {
+    let plugin = PluginCommand::find("catalog", Some(Path::new(PLUGIN_DIR))).unwrap();
+    assert_eq!(plugin.name(), "catalog");
+    assert!(PluginCommand::find("missing", Some(Path::new(PLUGIN_DIR))).is_none());
+    // Files that can't be run aren't plugins
+    #[cfg(unix)]
+    assert!(PluginCommand::find("disabled", Some(Path::new(PLUGIN_DIR))).is_none());
+}
+
+#[test]
+fn exports_and_touches() {
+    let processor = ProcessorCore::new();
+    let config = brix_cli::Config::default();
+    let context = AppContext::new(processor, &config);
+    context
+        .exports
+        .borrow_mut()
+        .insert(String::from("team"), json!("payments"));
+
+    let out = std::env::temp_dir().join(format!("brix-catalog-{}.json", std::process::id()));
+    let out = out.to_str().unwrap();
+    let plugin = PluginCommand::find("catalog", Some(Path::new(PLUGIN_DIR))).unwrap();
+    plugin
+        .run(
+            CommandParams::from(json!({
+                "out": out,
+                "context": { "module": "users" },
+            })),
+            &context,
+        )
+        .unwrap();
+
+    let input: Value = serde_json::from_str(&fs::read_to_string(out).unwrap()).unwrap();
+    fs::remove_file(out).unwrap();
+    assert_eq!(input["command"], "catalog");
+    assert_eq!(input["params"], json!({ "out": out }));
+    assert_eq!(
+        input["context"],
+        json!({ "module": "users", "team": "payments" })
+    );
+
+    assert_eq!(context.exports.borrow()["service_id"], "42");
+    assert_eq!(
+        context.journal.changes(),
+        vec![Change::Touched(PathBuf::from(out))]
+    );
+}
+
+#[test]
+fn failure() {
+    let processor = ProcessorCore::new();
+    let config = brix_cli::Config::default();
+    let context = AppContext::new(processor, &config);
+
+    let plugin = PluginCommand::find("fail", Some(Path::new(PLUGIN_DIR))).unwrap();
+    let err = plugin
+        .run(CommandParams::from(json!({})), &context)
+        .unwrap_err();
+    assert!(err.message.contains("the catalog is locked"));
+}
+
+#[test]
+fn large_input() {
+    let processor = ProcessorCore::new();
+    let config = brix_cli::Config::default();
+    let context = AppContext::new(processor, &config);
+
+    // Much larger than a pipe, which the plugin fills up with its output before reading everything
+    let notes = "a".repeat(1 << 20);
+    let plugin = PluginCommand::find("echo", Some(Path::new(PLUGIN_DIR))).unwrap();
+    plugin
+        .run(
+            CommandParams::from(json!({"context": {"notes": notes}})),
+            &context,
+        )
+        .unwrap();
+    assert_eq!(context.exports.borrow()["notes"], json!(notes));
+}
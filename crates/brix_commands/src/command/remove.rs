@@ -0,0 +1,107 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [RemoveCommand]
+
+use std::path::PathBuf;
+
+use log::info;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::command::{workdir_path, Command, CommandParams, PlannedAction};
+use crate::glob::{is_match, pattern_set};
+use brix_common::AppContext;
+use brix_errors::BrixError;
+
+#[cfg(test)]
+mod tests {
+    mod run;
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Params {
+    #[validate(required)]
+    destination: Option<PathBuf>,
+    glob: Option<String>,
+}
+
+/// The Brix remove command
+#[derive(Default)]
+pub struct RemoveCommand {}
+
+impl RemoveCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// The paths that would be removed, either the destination itself or the files inside of it
+    /// matching the glob. Nothing is removed if the destination doesn't exist.
+    fn paths(&self, cp: &Params, ctx: &AppContext) -> Result<Vec<PathBuf>, BrixError> {
+        let dest = workdir_path(cp.destination.as_ref().unwrap(), ctx)?;
+        let glob = match &cp.glob {
+            Some(glob) => glob,
+            None if ctx.fs.exists(&dest) || ctx.fs.is_symlink(&dest) => return Ok(vec![dest]),
+            None => return Ok(vec![]),
+        };
+
+        if !ctx.fs.is_dir(&dest) {
+            return Ok(vec![]);
+        }
+        let set = pattern_set(std::slice::from_ref(glob))?;
+        Ok(ctx
+            .fs
+            .walk(&dest)?
+            .into_iter()
+            .filter(|file| is_match(&set, file.strip_prefix(&dest).unwrap()))
+            .collect())
+    }
+}
+
+impl Command for RemoveCommand {
+    fn run(&self, params: CommandParams, ctx: &AppContext) -> Result<(), BrixError> {
+        let cp: Params = params.parse()?;
+
+        let paths = self.paths(&cp, ctx)?;
+        if paths.is_empty() {
+            info!(
+                "skipping: '{}', nothing to remove",
+                cp.destination.unwrap().display()
+            );
+            return Ok(());
+        }
+
+        for path in paths {
+            info!("removing '{}'", path.display());
+            // Links are removed without touching what they point to
+            if ctx.fs.is_dir(&path) && !ctx.fs.is_symlink(&path) {
+                ctx.journal.record_dir(&path)?;
+                ctx.fs.remove_dir_all(&path)?;
+            } else {
+                ctx.journal.record(&path)?;
+                ctx.fs.remove_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn plan(
+        &self,
+        params: CommandParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        let cp: Params = params.parse()?;
+        Ok(self
+            .paths(&cp, ctx)?
+            .into_iter()
+            .map(PlannedAction::Remove)
+            .collect())
+    }
+
+    fn name(&self) -> String {
+        String::from("remove")
+    }
+}
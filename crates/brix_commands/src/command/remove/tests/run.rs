@@ -0,0 +1,158 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::command::testing::Workspace;
+use crate::command::Command;
+use crate::{CommandParams, PlannedAction, RemoveCommand};
+use brix_common::Filesystem;
+
+const FILES: [&str; 4] = [
+    "app/README.md",
+    "app/src/main.rs",
+    "app/src/sample.rs.sample",
+    "app/tests/sample.rs.sample",
+];
+
+fn workspace() -> Workspace {
+    let files: Vec<(&str, &str)> = FILES.iter().map(|file| (*file, "")).collect();
+    Workspace::new(&files)
+}
+
+fn remaining(workspace: &Workspace) -> Vec<PathBuf> {
+    workspace
+        .fs
+        .walk(&workspace.path("app"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|file| {
+            file.strip_prefix(&workspace.config.workdir)
+                .unwrap()
+                .to_path_buf()
+        })
+        .collect()
+}
+
+fn params(destination: &str, glob: Value) -> CommandParams {
+    CommandParams::from(json!({"destination": destination, "glob": glob}))
+}
+
+#[test]
+fn remove_and_rollback() {
+    let workspace = workspace();
+    let context = workspace.context();
+    let command = RemoveCommand::new();
+
+    command
+        .run(params("app/README.md", Value::Null), &context)
+        .unwrap();
+    command
+        .run(params("app/src", Value::Null), &context)
+        .unwrap();
+    assert_eq!(
+        remaining(&workspace),
+        vec![PathBuf::from("app/tests/sample.rs.sample")]
+    );
+    // Removing what's already gone does nothing
+    command
+        .run(params("app/src", Value::Null), &context)
+        .unwrap();
+
+    context.journal.rollback().unwrap();
+    assert_eq!(
+        remaining(&workspace),
+        FILES.iter().map(PathBuf::from).collect::<Vec<PathBuf>>()
+    );
+}
+
+#[test]
+fn glob() {
+    let workspace = workspace();
+    let context = workspace.context();
+    let command = RemoveCommand::new();
+
+    assert_eq!(
+        command
+            .plan(params("app", json!("*.sample")), &context)
+            .unwrap(),
+        vec![
+            PlannedAction::Remove(workspace.path("app/src/sample.rs.sample")),
+            PlannedAction::Remove(workspace.path("app/tests/sample.rs.sample")),
+        ]
+    );
+    command
+        .run(params("app", json!("*.sample")), &context)
+        .unwrap();
+    assert_eq!(
+        remaining(&workspace),
+        vec![
+            PathBuf::from("app/README.md"),
+            PathBuf::from("app/src/main.rs")
+        ]
+    );
+}
+
+#[test]
+fn outside_workdir() {
+    let workspace = workspace();
+    let context = workspace.context();
+    let command = RemoveCommand::new();
+
+    for destination in ["../app", "app/../..", ".", "/"].iter() {
+        let err = command
+            .run(params(destination, Value::Null), &context)
+            .unwrap_err();
+        assert!(err
+            .message
+            .contains("isn't inside of the working directory"));
+    }
+    assert_eq!(remaining(&workspace).len(), FILES.len());
+}
+
+#[test]
+fn link_outside_workdir() {
+    let workspace = workspace();
+    let context = workspace.context();
+    let outside = Path::new("/brix-outside/secret.txt");
+    workspace.fs.write(outside, b"secret").unwrap();
+    workspace
+        .fs
+        .symlink(Path::new("/brix-outside"), &workspace.path("app/shared"))
+        .unwrap();
+    let command = RemoveCommand::new();
+
+    let err = command
+        .run(params("app/shared/secret.txt", Value::Null), &context)
+        .unwrap_err();
+    assert!(err
+        .message
+        .contains("isn't inside of the working directory"));
+
+    // Only the link is removed, not what it points to
+    command.run(params("app", json!("**")), &context).unwrap();
+    assert!(!workspace.fs.is_symlink(&workspace.path("app/shared")));
+    assert_eq!(workspace.fs.read(outside).unwrap(), b"secret");
+}
+
+#[test]
+fn rollback_empty_directories() {
+    let workspace = workspace();
+    workspace
+        .fs
+        .create_dir_all(&workspace.path("app/src/empty/nested"))
+        .unwrap();
+    let context = workspace.context();
+
+    RemoveCommand::new()
+        .run(params("app", Value::Null), &context)
+        .unwrap();
+    assert!(!workspace.fs.exists(&workspace.path("app")));
+
+    context.journal.rollback().unwrap();
+    assert!(workspace.fs.is_dir(&workspace.path("app/src/empty/nested")));
+    assert_eq!(remaining(&workspace).len(), FILES.len());
+}
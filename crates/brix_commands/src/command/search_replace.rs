@@ -5,15 +5,14 @@
 
 //! Contains [SearchReplaceCommand]
 
-use std::fs::{self, File};
-use std::io::Write;
 use std::path::PathBuf;
 
 use fancy_regex::Regex;
 use log::info;
+use serde::Deserialize;
 use validator::Validate;
 
-use crate::command::{Command, ProcessedCommandParams};
+use crate::command::{Command, CommandParams, PlannedAction};
 use crate::dir;
 use brix_common::AppContext;
 use brix_errors::BrixError;
@@ -21,6 +20,7 @@ use brix_errors::BrixError;
 #[cfg(test)]
 mod tests {
     mod invalid;
+    mod plan;
     mod run;
 }
 
@@ -39,7 +39,7 @@ impl PartialEq for SearchReplaceParams {
     }
 }
 
-#[derive(Debug, Validate)]
+#[derive(Debug, Deserialize, Validate)]
 struct Params {
     #[validate(required)]
     destination: Option<PathBuf>,
@@ -56,20 +56,27 @@ impl SearchReplaceCommand {
     pub fn new() -> Self {
         Self {}
     }
+
+    /// Parses the params, turning the escaped newlines and tabs of the search and replace into real ones.
+    fn params(&self, params: CommandParams) -> Result<Params, BrixError> {
+        let mut cp: Params = params.parse()?;
+        cp.search = cp.search.as_deref().map(unescape);
+        cp.replace = cp.replace.as_deref().map(unescape);
+        Ok(cp)
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\n", "\n").replace("\\t", "\t")
 }
 
 impl Command for SearchReplaceCommand {
-    fn run(&self, pcp: ProcessedCommandParams, ctx: &AppContext) -> Result<(), BrixError> {
-        let cp = Params {
-            destination: pcp.destination,
-            search: pcp.search,
-            replace: pcp.replace,
-        };
-        cp.validate()?;
+    fn run(&self, params: CommandParams, ctx: &AppContext) -> Result<(), BrixError> {
+        let cp = self.params(params)?;
 
         let dest = dir!(ctx.config.workdir, cp.destination.unwrap());
         info!("reading to string from '{}'", dest.clone().display());
-        let data = fs::read_to_string(dest.clone()).or_else(|err| {
+        let data = ctx.fs.read_to_string(&dest).or_else(|err| {
             return Err(BrixError::with(&format!(
                 "unable to read file '{}': {}",
                 dest.display(),
@@ -81,9 +88,9 @@ impl Command for SearchReplaceCommand {
         let re = Regex::new(&cp.search.unwrap())?;
         let result = re.replace_all(&data, cp.replace.unwrap());
 
+        ctx.journal.record(&dest)?;
         info!("writing changes");
-        let mut write = File::create(dest.clone()).unwrap();
-        write.write_all(result.as_bytes()).or_else(|_err| {
+        ctx.fs.write(&dest, result.as_bytes()).or_else(|_err| {
             return Err(BrixError::with(&format!(
                 "unable to write to file '{}'",
                 dest.display()
@@ -93,6 +100,33 @@ impl Command for SearchReplaceCommand {
         Ok(())
     }
 
+    fn plan(
+        &self,
+        params: CommandParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        let cp = self.params(params)?;
+
+        let dest = dir!(ctx.config.workdir, cp.destination.unwrap());
+        let search = cp.search.unwrap();
+        let re = Regex::new(&search)?;
+
+        // The file might still be created by an earlier command
+        let matches = match ctx.fs.exists(&dest) {
+            true => {
+                let data = ctx.fs.read_to_string(&dest)?;
+                Some(re.find_iter(&data).filter(|m| m.is_ok()).count())
+            }
+            false => None,
+        };
+
+        Ok(vec![PlannedAction::Edit {
+            path: dest,
+            search,
+            matches,
+        }])
+    }
+
     fn name(&self) -> String {
         String::from("search and replace")
     }
@@ -3,10 +3,10 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
-use std::path::PathBuf;
+use serde_json::json;
 
 use crate::command::Command;
-use crate::{ProcessedCommandParams, SearchReplaceCommand};
+use crate::{CommandParams, SearchReplaceCommand};
 use brix_common::AppContext;
 use brix_errors::BrixErrorKind;
 use brix_processor::ProcessorCore;
@@ -16,10 +16,7 @@ macro_rules! run {
         let processor = ProcessorCore::new();
         let config = brix_cli::Config::default();
         let command = SearchReplaceCommand::new();
-        let context = AppContext {
-            processor,
-            config: &config,
-        };
+        let context = AppContext::new(processor, &config);
         // Ensure it is a validation error
         assert_eq!(
             command.run($args, &context).unwrap_err().kind.unwrap(),
@@ -30,113 +27,60 @@ macro_rules! run {
 
 #[test]
 fn nothing() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: None,
-        overwrite: None,
-        search: None,
-        replace: None,
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({})))
 }
 
 #[test]
 fn destination() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: Some(PathBuf::new()),
-        overwrite: None,
-        search: None,
-        replace: None,
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "destination": "",
+    })))
 }
 
 #[test]
 fn search() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: None,
-        overwrite: None,
-        search: Some(String::new()),
-        replace: None,
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "search": "",
+    })))
 }
 
 #[test]
 fn replace() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: None,
-        overwrite: None,
-        search: None,
-        replace: Some(String::new()),
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "replace": "",
+    })))
 }
 
 #[test]
 fn destination_search() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: Some(PathBuf::new()),
-        overwrite: None,
-        search: Some(String::new()),
-        replace: None,
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "destination": "",
+        "search": "",
+    })))
 }
 
 #[test]
 fn destination_replace() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: Some(PathBuf::new()),
-        overwrite: None,
-        search: None,
-        replace: Some(String::new()),
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "destination": "",
+        "replace": "",
+    })))
 }
 
 #[test]
 fn search_replace() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: None,
-        overwrite: None,
-        search: Some(String::new()),
-        replace: Some(String::new()),
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "search": "",
+        "replace": "",
+    })))
 }
 
 #[test]
 #[should_panic]
 fn valid() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: Some(PathBuf::new()),
-        overwrite: None,
-        search: Some(String::new()),
-        replace: Some(String::new()),
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "destination": "",
+        "search": "",
+        "replace": "",
+    })))
 }
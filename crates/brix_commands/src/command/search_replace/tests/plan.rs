@@ -0,0 +1,62 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+use crate::command::Command;
+use crate::{CommandParams, PlannedAction, SearchReplaceCommand};
+use brix_common::AppContext;
+use brix_processor::ProcessorCore;
+
+macro_rules! do_plan {
+    ($path:expr, $search:expr) => {{
+        let processor = ProcessorCore::new();
+        let config = brix_cli::Config::default();
+        let command = SearchReplaceCommand::new();
+        let context = AppContext::new(processor, &config);
+
+        let args = CommandParams::from(json!({
+            "destination": $path,
+            "search": $search,
+            "replace": "replaced",
+        }));
+        command.plan(args, &context).unwrap()
+    }};
+}
+
+#[test]
+fn matches() {
+    let path = PathBuf::from("src/command/search_replace/plan.txt");
+    let contents = read_to_string(&path).unwrap();
+
+    let actions = do_plan!(&path, "fo+");
+    assert_eq!(
+        actions,
+        vec![PlannedAction::Edit {
+            path: std::env::current_dir().unwrap().join(&path),
+            search: String::from("fo+"),
+            matches: Some(3),
+        }]
+    );
+    // Planning never touches the file
+    assert_eq!(read_to_string(&path).unwrap(), contents);
+}
+
+#[test]
+fn missing_file() {
+    let actions = do_plan!("src/command/search_replace/missing.txt", "foo");
+    assert_eq!(
+        actions[0].to_string(),
+        format!(
+            "replace matches of 'foo' in '{}', which doesn't exist yet",
+            std::env::current_dir()
+                .unwrap()
+                .join("src/command/search_replace/missing.txt")
+                .display()
+        )
+    );
+}
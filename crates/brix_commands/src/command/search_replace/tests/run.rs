@@ -3,12 +3,13 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
-use std::fs::read_to_string;
+use serde_json::json;
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use crate::command::Command;
-use crate::{ProcessedCommandParams, SearchReplaceCommand};
-use brix_common::AppContext;
+use crate::{CommandParams, SearchReplaceCommand};
+use brix_common::{AppContext, Filesystem, MemoryFilesystem};
 use brix_processor::ProcessorCore;
 
 macro_rules! do_test {
@@ -16,35 +17,26 @@ macro_rules! do_test {
         let processor = ProcessorCore::new();
         let config = brix_cli::Config::default();
         let command = SearchReplaceCommand::new();
-        let context = AppContext {
-            processor,
-            config: &config,
-        };
+        // The file is only changed in memory
+        let fs = Rc::new(MemoryFilesystem::over_disk());
+        let context = AppContext::new(processor, &config).with_filesystem(fs.clone());
 
         let path = PathBuf::from("src/command/search_replace").join($path);
-        let contents = read_to_string(path.clone()).unwrap();
         let args = create_args!(path.clone(), $search, $replace);
         command.run(args, &context).unwrap();
 
-        let result = read_to_string(path.clone()).unwrap();
-        // Ensure file is reverted to its original state
-        std::fs::write(path, contents).unwrap();
+        let result = fs.read_to_string(&config.workdir.join(path)).unwrap();
         assert_eq!(result, $assertion);
     }};
 }
 
 macro_rules! create_args {
     ($dest:expr, $search:expr, $replace:expr) => {
-        ProcessedCommandParams {
-            source: None,
-            destination: Some(PathBuf::from($dest)),
-            overwrite: None,
-            search: Some(String::from($search)),
-            replace: Some(String::from($replace)),
-            commands: None,
-            stdout: None,
-            context: None,
-        }
+        CommandParams::from(json!({
+            "destination": $dest,
+            "search": $search,
+            "replace": $replace,
+        }))
     };
 }
 
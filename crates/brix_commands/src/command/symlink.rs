@@ -0,0 +1,149 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [SymlinkCommand]
+
+use std::path::{Component, Path, PathBuf};
+
+use dialoguer::console::Term;
+use log::debug;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::command::{workdir_path, CommandParams, OverwritableCommand, OverwritableParams};
+use brix_cli::OverwritePolicy;
+use brix_common::AppContext;
+use brix_errors::BrixError;
+
+#[cfg(test)]
+mod tests {
+    mod run;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SymlinkParams {
+    target: PathBuf,
+    destination: PathBuf,
+    overwrite: Option<OverwritePolicy>,
+    relative: bool,
+}
+
+impl OverwritableParams for SymlinkParams {
+    // The target is in the working directory, so it's checked along with the destination
+    fn source(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn destination(&self) -> PathBuf {
+        self.destination.clone()
+    }
+
+    fn overwrite(&self) -> Option<OverwritePolicy> {
+        self.overwrite
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Params {
+    #[validate(required)]
+    target: Option<PathBuf>,
+    #[validate(required)]
+    destination: Option<PathBuf>,
+    overwrite: Option<OverwritePolicy>,
+    relative: Option<bool>,
+}
+
+/// The Brix symlink command
+pub struct SymlinkCommand {
+    term: Term,
+}
+
+impl SymlinkCommand {
+    pub fn new() -> Self {
+        Self {
+            term: Term::stderr(),
+        }
+    }
+}
+
+impl Default for SymlinkCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The path from inside of the directory to the other path, both of which have to be normalized.
+fn relative_path(dir: &Path, path: &Path) -> PathBuf {
+    let dir: Vec<Component> = dir.components().collect();
+    let path: Vec<Component> = path.components().collect();
+    let common = dir
+        .iter()
+        .zip(path.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..dir.len() {
+        relative.push("..");
+    }
+    for component in &path[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+impl OverwritableCommand for SymlinkCommand {
+    type Params = SymlinkParams;
+
+    fn term(&self) -> Term {
+        self.term.clone()
+    }
+
+    fn from(&self, params: CommandParams) -> Result<SymlinkParams, BrixError> {
+        let cp: Params = params.parse()?;
+        Ok(Self::Params {
+            target: cp.target.unwrap(),
+            destination: cp.destination.unwrap(),
+            overwrite: cp.overwrite,
+            relative: cp.relative.unwrap_or(true),
+        })
+    }
+
+    fn destinations(
+        &self,
+        params: &SymlinkParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PathBuf>, BrixError> {
+        workdir_path(&params.target, ctx)?;
+        Ok(vec![workdir_path(&params.destination, ctx)?])
+    }
+
+    fn write_impl(&self, params: SymlinkParams, ctx: &AppContext) -> Result<(), BrixError> {
+        let target = workdir_path(&params.target, ctx)?;
+        let dest = workdir_path(&params.destination, ctx)?;
+
+        // Links and files are replaced, but a directory is never removed to make room for one
+        if ctx.fs.is_symlink(&dest) || ctx.fs.is_file(&dest) {
+            ctx.fs.remove_file(&dest)?;
+        } else if ctx.fs.is_dir(&dest) {
+            return Err(BrixError::with(&format!(
+                "unable to link '{}', it's a directory",
+                dest.display()
+            )));
+        }
+
+        let link = match params.relative {
+            true => relative_path(dest.parent().unwrap(), &target),
+            false => target,
+        };
+        debug!("linking '{}' to '{}'", dest.display(), link.display());
+        ctx.fs.symlink(&link, &dest)?;
+        Ok(())
+    }
+
+    fn name_inner(&self) -> String {
+        String::from("symlink")
+    }
+}
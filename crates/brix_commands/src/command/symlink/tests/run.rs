@@ -0,0 +1,61 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::command::symlink::relative_path;
+use crate::command::testing::Workspace;
+use crate::command::Command;
+use crate::{CommandParams, SymlinkCommand};
+use brix_common::Filesystem;
+
+const LINK: &str = "apps/web/.editorconfig";
+
+/// Links to the shared config in memory at `LINK`.
+fn link(target: &str, relative: Value) -> Result<Workspace, String> {
+    let workspace = Workspace::new(&[("shared/.editorconfig", "root = true")]);
+
+    let params = CommandParams::from(json!({
+        "target": target,
+        "destination": LINK,
+        "relative": relative,
+    }));
+    SymlinkCommand::new()
+        .run(params, &workspace.context())
+        .map_err(|err| err.message)?;
+    Ok(workspace)
+}
+
+#[test]
+fn relative() {
+    let workspace = link("shared/.editorconfig", Value::Null).unwrap();
+    assert!(workspace.fs.is_symlink(&workspace.path(LINK)));
+    assert_eq!(workspace.read(LINK), "root = true");
+}
+
+#[test]
+fn absolute() {
+    let workspace = link("shared/.editorconfig", json!(false)).unwrap();
+    assert_eq!(workspace.read(LINK), "root = true");
+}
+
+#[test]
+fn outside_workdir() {
+    let err = link("../.editorconfig", Value::Null).err().unwrap();
+    assert!(err.contains("isn't inside of the working directory"));
+}
+
+#[test]
+fn relative_paths() {
+    assert_eq!(
+        relative_path(Path::new("/work/apps/web"), Path::new("/work/shared/a")),
+        PathBuf::from("../../shared/a")
+    );
+    assert_eq!(
+        relative_path(Path::new("/work"), Path::new("/work/shared/a")),
+        PathBuf::from("shared/a")
+    );
+}
@@ -6,23 +6,26 @@
 //! Contains [TemplateCommand]
 
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 use dialoguer::console::Term;
-use log::debug;
-use validator::{Validate, ValidationErrors};
+use log::{debug, info};
+use serde::Deserialize;
+use serde_json::Value;
+use validator::Validate;
 
 use crate::{
-    command::{OverwritableCommand, OverwritableParams, ProcessedCommandParams},
+    command::{is_unchanged, merge_update, CommandParams, OverwritableCommand, OverwritableParams},
     dir,
+    glob::{is_match, patterns},
 };
+use brix_cli::OverwritePolicy;
 use brix_common::AppContext;
 use brix_errors::BrixError;
 
 #[cfg(test)]
 mod tests {
+    mod dir;
     mod invalid;
     mod run;
 }
@@ -31,8 +34,9 @@ mod tests {
 pub struct TemplateParams {
     source: PathBuf,
     destination: PathBuf,
-    overwrite: Option<bool>,
-    context: Option<HashMap<String, String>>,
+    overwrite: Option<OverwritePolicy>,
+    context: Option<HashMap<String, Value>>,
+    verbatim: Vec<String>,
 }
 
 impl PartialEq for TemplateParams {
@@ -40,20 +44,31 @@ impl PartialEq for TemplateParams {
         return self.source == other.source
             && self.destination == other.destination
             && self.overwrite == other.overwrite
-            && self.context == other.context;
+            && self.context == other.context
+            && self.verbatim == other.verbatim;
     }
 }
 
+/// A file of a templated directory.
+#[derive(Debug)]
+struct TemplateFile {
+    source: PathBuf,
+    /// The rendered path of the file, relative to the destination.
+    destination: PathBuf,
+    /// Whether the file is copied without rendering it.
+    verbatim: bool,
+}
+
 impl OverwritableParams for TemplateParams {
-    fn source(&self) -> PathBuf {
-        self.source.clone()
+    fn source(&self) -> Option<PathBuf> {
+        Some(self.source.clone())
     }
 
     fn destination(&self) -> PathBuf {
         self.destination.clone()
     }
 
-    fn overwrite(&self) -> Option<bool> {
+    fn overwrite(&self) -> Option<OverwritePolicy> {
         self.overwrite
     }
 }
@@ -72,14 +87,165 @@ impl TemplateCommand {
     }
 }
 
-#[derive(Debug, Validate)]
+impl TemplateCommand {
+    /// Renders the source template with the context.
+    fn render(&self, params: &TemplateParams, ctx: &AppContext) -> Result<String, BrixError> {
+        let source = dir!(ctx.config.workdir, &params.source);
+        self.render_file(&source, params, ctx)
+    }
+
+    fn render_file(
+        &self,
+        source: &Path,
+        params: &TemplateParams,
+        ctx: &AppContext,
+    ) -> Result<String, BrixError> {
+        let contents = ctx.fs.read_to_string(source)?;
+        debug!("templating '{}'", source.display());
+        self.render_text(contents, params, ctx)
+    }
+
+    fn render_text(
+        &self,
+        text: String,
+        params: &TemplateParams,
+        ctx: &AppContext,
+    ) -> Result<String, BrixError> {
+        let context = params.context.clone().unwrap_or_default();
+        let processed_context = brix_processor::create_context(context);
+        ctx.processor.process(text, processed_context)
+    }
+
+    /// Every file of a source directory along with where it's written to. The names of
+    /// the files and directories are rendered and a trailing `.hbs` is dropped, except for
+    /// verbatim files. Files whose name renders to nothing are left out.
+    fn files(
+        &self,
+        params: &TemplateParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<TemplateFile>, BrixError> {
+        let source = dir!(ctx.config.workdir, &params.source);
+        let all: Vec<PathBuf> = ctx
+            .fs
+            .walk(&source)?
+            .iter()
+            .map(|file| file.strip_prefix(&source).unwrap().to_path_buf())
+            .collect();
+        let verbatim = patterns(&params.verbatim, &all, &source)?;
+
+        let mut files = Vec::new();
+        for file in all {
+            let is_verbatim = is_match(&verbatim, &file);
+            let mut rendered = match self.render_name(&file, params, ctx)? {
+                Some(rendered) => rendered,
+                None => {
+                    debug!("leaving out '{}', its name is empty", file.display());
+                    continue;
+                }
+            };
+            let name = rendered.file_name().unwrap().to_string_lossy().to_string();
+            if !is_verbatim && name.len() > ".hbs".len() && name.ends_with(".hbs") {
+                rendered.set_file_name(&name[..name.len() - ".hbs".len()]);
+            }
+            files.push(TemplateFile {
+                source: source.join(&file),
+                destination: rendered,
+                verbatim: is_verbatim,
+            });
+        }
+        Ok(files)
+    }
+
+    /// Renders each part of the path of a file in a source directory, or returns `None` if one
+    /// of them renders to nothing. A part can render to several directories, but never to
+    /// anything that leads out of the destination.
+    fn render_name(
+        &self,
+        file: &Path,
+        params: &TemplateParams,
+        ctx: &AppContext,
+    ) -> Result<Option<PathBuf>, BrixError> {
+        let mut rendered = PathBuf::new();
+        for component in file.components() {
+            let name = component.as_os_str().to_string_lossy().to_string();
+            let part = self.render_text(name, params, ctx)?;
+            if part.is_empty() {
+                return Ok(None);
+            }
+            let part = PathBuf::from(part);
+            if part
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_)))
+            {
+                return Err(BrixError::with(&format!(
+                    "the name of '{}' renders to '{}', which isn't inside of the destination",
+                    file.display(),
+                    rendered.join(&part).display()
+                )));
+            }
+            rendered.push(part);
+        }
+        Ok(Some(rendered))
+    }
+
+    /// Renders every file of the source directory into the destination. When merging,
+    /// files that already exist have the update merged into them, except for verbatim files
+    /// which are only replaced if they haven't changed since they were generated.
+    fn write_dir(
+        &self,
+        params: TemplateParams,
+        ctx: &AppContext,
+        merge: bool,
+    ) -> Result<(), BrixError> {
+        let dest = dir!(ctx.config.workdir, &params.destination);
+        let files = self.files(&params, ctx)?;
+        info!(
+            "templating {} files from '{}'",
+            files.len(),
+            params.source.display()
+        );
+
+        for file in files {
+            let target = dest.join(&file.destination);
+            if let Some(parent) = target.parent() {
+                ctx.fs.create_dir_all(parent)?;
+            }
+            if merge && ctx.fs.is_file(&target) {
+                if !file.verbatim {
+                    let result = self.render_file(&file.source, &params, ctx)?;
+                    merge_update(&target, result.into_bytes(), ctx)?;
+                    continue;
+                }
+                if !is_unchanged(std::slice::from_ref(&target), ctx)? {
+                    info!(
+                        "skipping: '{}', changed since it was generated",
+                        target.display()
+                    );
+                    continue;
+                }
+            }
+            if file.verbatim {
+                ctx.fs.copy(&file.source, &target)?;
+                continue;
+            }
+            let result = self.render_file(&file.source, &params, ctx)?;
+            ctx.journal
+                .record_render(&target, result.clone().into_bytes());
+            ctx.fs.write(&target, result.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
 struct Params {
     #[validate(required)]
     source: Option<PathBuf>,
     #[validate(required)]
     destination: Option<PathBuf>,
-    overwrite: Option<bool>,
-    context: Option<HashMap<String, String>>,
+    overwrite: Option<OverwritePolicy>,
+    context: Option<HashMap<String, Value>>,
+    verbatim: Option<Vec<String>>,
 }
 
 impl OverwritableCommand for TemplateCommand {
@@ -89,34 +255,64 @@ impl OverwritableCommand for TemplateCommand {
         self.term.clone()
     }
 
-    fn from(&self, pcp: ProcessedCommandParams) -> Result<TemplateParams, ValidationErrors> {
-        let cp = Params {
-            source: pcp.source,
-            destination: pcp.destination,
-            overwrite: pcp.overwrite,
-            context: pcp.context,
-        };
-        cp.validate()?;
+    fn from(&self, params: CommandParams) -> Result<TemplateParams, BrixError> {
+        let cp: Params = params.parse()?;
         Ok(Self::Params {
             source: cp.source.unwrap(),
             destination: cp.destination.unwrap(),
             overwrite: cp.overwrite,
             context: cp.context,
+            verbatim: cp.verbatim.unwrap_or_default(),
         })
     }
 
-    fn write_impl(&self, params: TemplateParams, ctx: &AppContext) -> Result<(), BrixError> {
-        let source = dir!(ctx.config.workdir, params.source);
-        let mut file = File::open(&source)?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
+    fn destinations(
+        &self,
+        params: &TemplateParams,
+        ctx: &AppContext,
+    ) -> Result<Vec<PathBuf>, BrixError> {
+        let dest = dir!(ctx.config.workdir, &params.destination);
+        if !ctx.fs.is_dir(&dir!(ctx.config.workdir, &params.source)) {
+            return Ok(vec![dest]);
+        }
+        Ok(self
+            .files(params, ctx)?
+            .into_iter()
+            .map(|file| dest.join(file.destination))
+            .collect())
+    }
 
-        debug!("templating '{}'", source.display());
-        let context = params.context.unwrap_or(HashMap::new());
-        let processed_context = brix_processor::create_context(context);
-        let result = ctx.processor.process(contents, processed_context)?;
+    fn contents(
+        &self,
+        params: &TemplateParams,
+        ctx: &AppContext,
+    ) -> Result<Option<Vec<u8>>, BrixError> {
+        // The files of a directory are merged one by one with `merge_files`
+        if ctx.fs.is_dir(&dir!(ctx.config.workdir, &params.source)) {
+            return Ok(None);
+        }
+        Ok(Some(self.render(params, ctx)?.into_bytes()))
+    }
+
+    fn merges_files(&self, params: &TemplateParams, ctx: &AppContext) -> Result<bool, BrixError> {
+        Ok(ctx.fs.is_dir(&dir!(ctx.config.workdir, &params.source)))
+    }
+
+    fn merge_files(&self, params: TemplateParams, ctx: &AppContext) -> Result<(), BrixError> {
+        self.write_dir(params, ctx, true)
+    }
+
+    fn write_impl(&self, params: TemplateParams, ctx: &AppContext) -> Result<(), BrixError> {
+        if ctx.fs.is_dir(&dir!(ctx.config.workdir, &params.source)) {
+            return self.write_dir(params, ctx, false);
+        }
+        let result = self.render(&params, ctx)?;
 
-        std::fs::write(params.destination, result)?;
+        // Kept so that `brix update` can merge against it next time
+        let dest = dir!(ctx.config.workdir, &params.destination);
+        ctx.journal
+            .record_render(&dest, result.clone().into_bytes());
+        ctx.fs.write(&dest, result.as_bytes())?;
 
         Ok(())
     }
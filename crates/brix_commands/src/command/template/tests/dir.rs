@@ -0,0 +1,148 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::command::{Command, PlannedAction};
+use crate::{CommandParams, TemplateCommand};
+use brix_common::history::Manifest;
+use brix_common::{AppContext, Filesystem, MemoryFilesystem};
+use brix_processor::ProcessorCore;
+
+const SOURCE: &str = "src/command/template/skeleton";
+
+fn params(verbatim: Value) -> CommandParams {
+    CommandParams::from(json!({
+        "source": SOURCE,
+        "destination": "app",
+        "context": {"project": "com.acme.shop"},
+        "verbatim": verbatim,
+    }))
+}
+
+#[test]
+fn run() {
+    let config = brix_cli::Config::default();
+    let fs = Rc::new(MemoryFilesystem::over_disk());
+    let context = AppContext::new(ProcessorCore::new(), &config).with_filesystem(fs.clone());
+
+    TemplateCommand::new()
+        .run(params(json!(["gradle/"])), &context)
+        .unwrap();
+
+    let app = config.workdir.join("app");
+    let files: BTreeMap<PathBuf, String> = fs
+        .files()
+        .into_iter()
+        .map(|(path, contents)| {
+            let path = path.strip_prefix(&app).unwrap().to_path_buf();
+            (path, String::from_utf8(contents).unwrap())
+        })
+        .collect();
+
+    // The docs directory renders to nothing, so it's left out
+    assert_eq!(
+        files.keys().cloned().collect::<Vec<PathBuf>>(),
+        vec![
+            PathBuf::from("gradle/wrapper.properties"),
+            PathBuf::from("settings.gradle.kts"),
+            PathBuf::from("src/com/acme/shop/App.java"),
+        ]
+    );
+    assert_eq!(
+        files[&PathBuf::from("gradle/wrapper.properties")],
+        "distributionUrl={{gradle_url}}\n"
+    );
+    assert_eq!(
+        files[&PathBuf::from("settings.gradle.kts")],
+        "rootProject.name = \"com.acme.shop\"\n"
+    );
+    assert!(
+        files[&PathBuf::from("src/com/acme/shop/App.java")].starts_with("package com.acme.shop;")
+    );
+}
+
+#[test]
+fn plan() {
+    let config = brix_cli::Config::default();
+    let context = AppContext::new(ProcessorCore::new(), &config);
+
+    let actions = TemplateCommand::new()
+        .plan(params(Value::Null), &context)
+        .unwrap();
+    let app = config.workdir.join("app");
+    assert_eq!(
+        actions,
+        vec![
+            PlannedAction::Create(app.join("gradle/wrapper.properties")),
+            PlannedAction::Create(app.join("settings.gradle.kts")),
+            PlannedAction::Create(app.join("src/com/acme/shop/App.java")),
+        ]
+    );
+
+    let error = TemplateCommand::new()
+        .plan(params(json!(["*.jar"])), &context)
+        .unwrap_err();
+    assert!(error.message.contains("'*.jar' does not match any files"));
+}
+
+#[test]
+fn outside_destination() {
+    let config = brix_cli::Config::default();
+    let fs = Rc::new(MemoryFilesystem::over_disk());
+    let context = AppContext::new(ProcessorCore::new(), &config).with_filesystem(fs.clone());
+
+    for docs in ["../../etc", "/etc"].iter() {
+        let params = CommandParams::from(json!({
+            "source": SOURCE,
+            "destination": "app",
+            "context": {"project": "com.acme.shop", "docs": docs},
+        }));
+        let error = TemplateCommand::new().run(params, &context).unwrap_err();
+        assert!(error.message.contains("isn't inside of the destination"));
+    }
+    assert!(fs.files().is_empty());
+}
+
+#[test]
+fn merge() {
+    let config = brix_cli::Config::default();
+    let fs = Rc::new(MemoryFilesystem::over_disk());
+    let context = AppContext::new(ProcessorCore::new(), &config).with_filesystem(fs.clone());
+    TemplateCommand::new()
+        .run(params(Value::Null), &context)
+        .unwrap();
+    Manifest::record(&*fs, &config, HashMap::new(), &context.journal)
+        .unwrap()
+        .write(&*fs, &config.workdir)
+        .unwrap();
+
+    let settings = config.workdir.join("app/settings.gradle.kts");
+    let edited = "rootProject.name = \"com.acme.shop\"\ninclude(\"api\")\n";
+    fs.write(&settings, edited.as_bytes()).unwrap();
+
+    let params = CommandParams::from(json!({
+        "source": SOURCE,
+        "destination": "app",
+        "context": {"project": "com.acme.store"},
+        "overwrite": "merge",
+    }));
+    TemplateCommand::new().run(params, &context).unwrap();
+
+    // The edit touches the same line as the update, so both are kept
+    assert_eq!(
+        fs.read_to_string(&settings).unwrap(),
+        "<<<<<<< current\n\
+         rootProject.name = \"com.acme.shop\"\n\
+         include(\"api\")\n\
+         =======\n\
+         rootProject.name = \"com.acme.store\"\n\
+         >>>>>>> update\n"
+    );
+    assert!(fs.is_file(&config.workdir.join("app/src/com/acme/store/App.java")));
+}
@@ -3,10 +3,10 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
-use std::path::PathBuf;
+use serde_json::json;
 
 use crate::command::Command;
-use crate::{ProcessedCommandParams, TemplateCommand};
+use crate::{CommandParams, TemplateCommand};
 use brix_common::AppContext;
 use brix_errors::BrixErrorKind;
 use brix_processor::ProcessorCore;
@@ -16,10 +16,7 @@ macro_rules! run {
         let processor = ProcessorCore::new();
         let config = brix_cli::Config::default();
         let command = TemplateCommand::new();
-        let context = AppContext {
-            processor,
-            config: &config,
-        };
+        let context = AppContext::new(processor, &config);
         // Ensure it is a validation error
         assert_eq!(
             command.run($args, &context).unwrap_err().kind.unwrap(),
@@ -30,57 +27,28 @@ macro_rules! run {
 
 #[test]
 fn nothing() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: None,
-        overwrite: None,
-        search: None,
-        replace: None,
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({})))
 }
 
 #[test]
 fn source() {
-    run!(ProcessedCommandParams {
-        source: Some(PathBuf::new()),
-        destination: None,
-        overwrite: None,
-        search: None,
-        replace: None,
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "source": "",
+    })))
 }
 
 #[test]
 fn destination() {
-    run!(ProcessedCommandParams {
-        source: None,
-        destination: Some(PathBuf::new()),
-        overwrite: None,
-        search: None,
-        replace: None,
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "destination": "",
+    })))
 }
 
 #[test]
 #[should_panic]
 fn valid() {
-    run!(ProcessedCommandParams {
-        source: Some(PathBuf::new()),
-        destination: Some(PathBuf::new()),
-        overwrite: None,
-        search: None,
-        replace: None,
-        commands: None,
-        stdout: None,
-        context: None,
-    })
+    run!(CommandParams::from(json!({
+        "source": "",
+        "destination": "",
+    })))
 }
@@ -3,25 +3,26 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
-use std::fs::{read_to_string, remove_file};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use maplit::*;
+use serde_json::{json, Value};
 
 use crate::command::Command;
-use crate::{ProcessedCommandParams, TemplateCommand};
+use crate::{CommandParams, TemplateCommand};
 
-use brix_common::AppContext;
+use brix_common::{AppContext, Filesystem, MemoryFilesystem};
 use brix_processor::ProcessorCore;
 
 macro_rules! do_test {
     ($source:expr, $context:expr, $assertion:expr) => {{
         let processor = ProcessorCore::new();
         let config = brix_cli::Config::default();
-        let context = AppContext {
-            processor,
-            config: &config,
-        };
+        // The output is only written to memory
+        let fs = Rc::new(MemoryFilesystem::over_disk());
+        let context = AppContext::new(processor, &config).with_filesystem(fs.clone());
 
         let path = PathBuf::from("src/command/template").join($source);
         let temp_dir = "src/command/template/temp/";
@@ -32,25 +33,21 @@ macro_rules! do_test {
         let command = TemplateCommand::new();
         command.run(args, &context).unwrap();
 
-        let result = read_to_string(PathBuf::from(destination.clone())).unwrap();
-        // Ensure that the file is removed from the temp directory
-        remove_file(destination).unwrap();
+        let result = fs
+            .read_to_string(&config.workdir.join(destination))
+            .unwrap();
         assert_eq!(result, $assertion);
     }};
 }
 
 macro_rules! create_args {
     ($source:expr, $destination:expr, $context:expr) => {
-        ProcessedCommandParams {
-            source: Some(PathBuf::from($source)),
-            destination: Some(PathBuf::from($destination)),
-            overwrite: Some(true),
-            search: None,
-            replace: None,
-            commands: None,
-            stdout: None,
-            context: Some($context),
-        }
+        CommandParams::from(json!({
+            "source": $source,
+            "destination": $destination,
+            "overwrite": "always",
+            "context": $context,
+        }))
     };
 }
 
@@ -119,5 +116,23 @@ fn unchanged() {
 
 #[test]
 fn empty_tags() {
-    do_test!("empty_tags.hbs", hashmap! {}, "The  here will be \n");
+    do_test!(
+        "empty_tags.hbs",
+        HashMap::<String, Value>::new(),
+        "The  here will be \n"
+    );
+}
+
+#[test]
+fn nested_context() {
+    do_test!(
+        "nested_context.hbs",
+        hashmap! {
+            s!("db") => json!({"host": "localhost", "port": 5432}),
+            s!("enabled") => json!(true),
+            s!("disabled") => json!(false),
+            s!("items") => json!(["one", "two"])
+        },
+        "localhost:5432\nenabled\none;two;\n"
+    );
 }
@@ -0,0 +1,51 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains the [Workspace] that the tests of the commands which change files run in.
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use brix_common::{AppContext, Filesystem, MemoryFilesystem};
+use brix_processor::ProcessorCore;
+
+/// A working directory in memory, so that the tests never touch the disk.
+pub(crate) struct Workspace {
+    pub config: brix_cli::Config,
+    pub fs: Rc<MemoryFilesystem>,
+}
+
+impl Workspace {
+    /// Creates the workspace with the files and their contents, relative to the working directory.
+    pub fn new(files: &[(&str, &str)]) -> Self {
+        let workspace = Self {
+            config: brix_cli::Config::default(),
+            fs: Rc::new(MemoryFilesystem::new()),
+        };
+        for (path, contents) in files.iter() {
+            workspace
+                .fs
+                .write(&workspace.path(path), contents.as_bytes())
+                .unwrap();
+        }
+        workspace
+    }
+
+    /// A context that runs commands against the workspace, which all the commands of a test
+    /// have to share to be rolled back together.
+    pub fn context(&self) -> AppContext<'_> {
+        AppContext::new(ProcessorCore::new(), &self.config).with_filesystem(self.fs.clone())
+    }
+
+    /// The path joined to the working directory.
+    pub fn path<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.config.workdir.join(path)
+    }
+
+    /// The contents of the file relative to the working directory.
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> String {
+        self.fs.read_to_string(&self.path(path)).unwrap()
+    }
+}
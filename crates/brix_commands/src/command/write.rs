@@ -0,0 +1,178 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [WriteCommand]
+
+use std::path::PathBuf;
+
+use dialoguer::console::Term;
+use log::info;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::{
+    command::{CommandParams, OverwritableCommand, OverwritableParams},
+    dir,
+};
+use brix_cli::OverwritePolicy;
+use brix_common::AppContext;
+use brix_errors::{BrixError, BrixErrorKind};
+
+#[cfg(test)]
+mod tests {
+    mod run;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Replace,
+    Append,
+    Prepend,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WriteParams {
+    destination: PathBuf,
+    content: String,
+    overwrite: Option<OverwritePolicy>,
+    mode: Mode,
+}
+
+impl OverwritableParams for WriteParams {
+    fn source(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn destination(&self) -> PathBuf {
+        self.destination.clone()
+    }
+
+    fn overwrite(&self) -> Option<OverwritePolicy> {
+        self.overwrite
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Params {
+    #[validate(required)]
+    destination: Option<PathBuf>,
+    #[validate(required)]
+    content: Option<String>,
+    overwrite: Option<OverwritePolicy>,
+    append: Option<bool>,
+    prepend: Option<bool>,
+}
+
+/// The Brix write command
+pub struct WriteCommand {
+    term: Term,
+}
+
+impl WriteCommand {
+    pub fn new() -> Self {
+        Self {
+            term: Term::stderr(),
+        }
+    }
+
+    /// What the destination ends up with, or `None` if the content was already added to it.
+    fn result(&self, params: &WriteParams, ctx: &AppContext) -> Result<Option<String>, BrixError> {
+        let dest = dir!(ctx.config.workdir, &params.destination);
+        if params.mode == Mode::Replace || !ctx.fs.is_file(&dest) {
+            return Ok(Some(params.content.clone()));
+        }
+
+        let current = ctx.fs.read_to_string(&dest)?;
+        let content = &params.content;
+        let result = match params.mode {
+            Mode::Append if current.ends_with(content.as_str()) => return Ok(None),
+            Mode::Prepend if current.starts_with(content.as_str()) => return Ok(None),
+            Mode::Append if current.is_empty() || current.ends_with('\n') => current + content,
+            Mode::Append => format!("{}\n{}", current, content),
+            Mode::Prepend if content.ends_with('\n') => format!("{}{}", content, current),
+            _ => format!("{}\n{}", content, current),
+        };
+        Ok(Some(result))
+    }
+}
+
+impl Default for WriteCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OverwritableCommand for WriteCommand {
+    type Params = WriteParams;
+
+    fn term(&self) -> Term {
+        self.term.clone()
+    }
+
+    fn from(&self, params: CommandParams) -> Result<WriteParams, BrixError> {
+        let cp: Params = params.parse()?;
+        let mode = match (cp.append.unwrap_or(false), cp.prepend.unwrap_or(false)) {
+            (false, false) => Mode::Replace,
+            (true, false) => Mode::Append,
+            (false, true) => Mode::Prepend,
+            (true, true) => {
+                return Err(BrixError {
+                    kind: Some(BrixErrorKind::Validation),
+                    message: String::from("only one of 'append' or 'prepend' can be set"),
+                })
+            }
+        };
+
+        // Adding to a file doesn't replace what's in it, so there's nothing to ask about
+        let overwrite = match mode {
+            Mode::Replace => cp.overwrite,
+            _ => cp.overwrite.or(Some(OverwritePolicy::Always)),
+        };
+
+        // The content was already rendered along with the rest of the params
+        Ok(Self::Params {
+            destination: cp.destination.unwrap(),
+            content: cp.content.unwrap(),
+            overwrite,
+            mode,
+        })
+    }
+
+    fn contents(
+        &self,
+        params: &WriteParams,
+        _ctx: &AppContext,
+    ) -> Result<Option<Vec<u8>>, BrixError> {
+        // Merging would add the content again
+        match params.mode {
+            Mode::Replace => Ok(Some(params.content.clone().into_bytes())),
+            _ => Ok(None),
+        }
+    }
+
+    fn write_impl(&self, params: WriteParams, ctx: &AppContext) -> Result<(), BrixError> {
+        let dest = dir!(ctx.config.workdir, &params.destination);
+        let result = match self.result(&params, ctx)? {
+            Some(result) => result,
+            None => {
+                info!("skipping: '{}', already has the content", dest.display());
+                return Ok(());
+            }
+        };
+
+        // Kept so that `brix update` can merge against it next time
+        if params.mode == Mode::Replace {
+            ctx.journal
+                .record_render(&dest, result.clone().into_bytes());
+        }
+        ctx.fs.write(&dest, result.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn name_inner(&self) -> String {
+        String::from("write")
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+
+use crate::command::testing::Workspace;
+use crate::command::Command;
+use crate::{CommandParams, PlannedAction, WriteCommand};
+use brix_errors::{BrixError, BrixErrorKind};
+
+/// Runs the write command over the file in memory, which exists if it has contents,
+/// and returns what's in the file afterwards.
+fn write(existing: Option<&str>, params: Value) -> Result<String, BrixError> {
+    let files: Vec<(&str, &str)> = existing
+        .map(|existing| (".gitignore", existing))
+        .into_iter()
+        .collect();
+    let workspace = Workspace::new(&files);
+
+    let mut params = params;
+    params["destination"] = json!(".gitignore");
+    WriteCommand::new().run(CommandParams::from(params), &workspace.context())?;
+    Ok(workspace.read(".gitignore"))
+}
+
+#[test]
+fn replace() {
+    let params = json!({"content": "dist/\n", "overwrite": "always"});
+    assert_eq!(write(None, params.clone()).unwrap(), "dist/\n");
+    assert_eq!(write(Some("target/\n"), params).unwrap(), "dist/\n");
+
+    let params = json!({"content": "dist/\n", "overwrite": "never"});
+    assert_eq!(write(Some("target/\n"), params).unwrap(), "target/\n");
+}
+
+#[test]
+fn append_and_prepend() {
+    let append = json!({"content": "dist/\n", "append": true});
+    assert_eq!(write(None, append.clone()).unwrap(), "dist/\n");
+    assert_eq!(
+        write(Some("target/"), append.clone()).unwrap(),
+        "target/\ndist/\n"
+    );
+    // Running again doesn't add the content twice
+    assert_eq!(
+        write(Some("target/\ndist/\n"), append).unwrap(),
+        "target/\ndist/\n"
+    );
+
+    let prepend = json!({"content": "# Generated", "prepend": true});
+    assert_eq!(
+        write(Some("target/\n"), prepend).unwrap(),
+        "# Generated\ntarget/\n"
+    );
+}
+
+#[test]
+fn invalid() {
+    let error = write(None, json!({"append": true})).unwrap_err();
+    assert_eq!(error.kind.unwrap(), BrixErrorKind::Validation);
+
+    let error = write(
+        None,
+        json!({"content": "", "append": true, "prepend": true}),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind.unwrap(), BrixErrorKind::Validation);
+}
+
+#[test]
+fn merge_without_history() {
+    let workspace = Workspace::new(&[(".gitignore", "target/\n")]);
+    let context = workspace.context();
+    let params = json!({"destination": ".gitignore", "content": "dist/\n", "overwrite": "merge"});
+
+    // Brix never rendered the file, so there's nothing to merge with
+    assert_eq!(
+        WriteCommand::new()
+            .plan(CommandParams::from(params.clone()), &context)
+            .unwrap(),
+        vec![PlannedAction::Backup(workspace.path(".gitignore"))]
+    );
+    WriteCommand::new()
+        .run(CommandParams::from(params), &context)
+        .unwrap();
+    assert_eq!(workspace.read(".gitignore"), "dist/\n");
+    assert_eq!(workspace.read(".gitignore.orig"), "target/\n");
+}
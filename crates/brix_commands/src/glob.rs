@@ -0,0 +1,61 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Glob patterns for picking files out of a source directory.
+
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+
+use brix_errors::BrixError;
+
+/// Builds the pattern into a glob. Like a `.gitignore`, a pattern without a `/`
+/// matches at any depth, and a trailing `/` is allowed for directories.
+fn glob(pattern: &str) -> Result<Glob, BrixError> {
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = match trimmed.contains('/') {
+        true => trimmed.trim_start_matches('/').to_string(),
+        false => format!("**/{}", trimmed),
+    };
+    Ok(GlobBuilder::new(&anchored)
+        .literal_separator(true)
+        .build()?)
+}
+
+pub fn pattern_set(patterns: &[String]) -> Result<GlobSet, BrixError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(glob(pattern)?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Builds the patterns, failing when one of them doesn't match any of the files
+/// since it's most likely a typo.
+pub fn patterns(
+    patterns: &[String],
+    files: &[PathBuf],
+    source: &Path,
+) -> Result<GlobSet, BrixError> {
+    for pattern in patterns {
+        let set = pattern_set(std::slice::from_ref(pattern))?;
+        if !files.iter().any(|file| is_match(&set, file)) {
+            return Err(BrixError::with(&format!(
+                "pattern '{}' does not match any files in '{}'",
+                pattern,
+                source.display()
+            )));
+        }
+    }
+    pattern_set(patterns)
+}
+
+/// Whether the file or one of the directories it's in matches, so that excluding
+/// a directory excludes everything inside of it.
+pub fn is_match(set: &GlobSet, file: &Path) -> bool {
+    file.ancestors()
+        .filter(|path| !path.as_os_str().is_empty())
+        .any(|path| set.is_match(path))
+}
@@ -8,10 +8,21 @@
 
 pub use command::copy::CopyCommand;
 pub use command::exec::ExecCommand;
+pub use command::generate::{GenerateCommand, Generator};
+pub use command::inject::InjectCommand;
 pub use command::mkdir::MkdirCommand;
+pub use command::plugin::PluginCommand;
+pub use command::r#move::MoveCommand;
+pub use command::remove::RemoveCommand;
 pub use command::search_replace::SearchReplaceCommand;
+pub use command::symlink::SymlinkCommand;
 pub use command::template::TemplateCommand;
-pub use command::{Command, ProcessedCommandParams};
+pub use command::write::WriteCommand;
+pub use command::{Command, CommandParams, PlannedAction};
+pub use registry::{CommandFactory, CommandRegistry};
 
 mod command;
+mod glob;
 mod macros;
+mod merge;
+mod registry;
@@ -0,0 +1,140 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [merge], the line based three-way merge used by `brix update`,
+//! and [diff], which shows what overwriting a file would change.
+
+use colored::*;
+use difflib::sequencematcher::SequenceMatcher;
+
+#[cfg(test)]
+mod tests {
+    mod diff;
+    mod merge;
+}
+
+const CURRENT_MARKER: &str = "<<<<<<< current\n";
+const SEPARATOR_MARKER: &str = "=======\n";
+const UPDATE_MARKER: &str = ">>>>>>> update\n";
+
+/// The result of merging the current contents of a file with an update.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Merged {
+    pub contents: String,
+    /// The number of hunks changed in both that are left between conflict markers.
+    pub conflicts: usize,
+}
+
+/// Merges the changes made since `base` in both `current` and `update`.
+/// Hunks changed on only one side take that side, and hunks changed differently on
+/// both sides are kept between `<<<<<<< current` and `>>>>>>> update` markers.
+pub(crate) fn merge(base: &str, current: &str, update: &str) -> Merged {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let current: Vec<&str> = current.split_inclusive('\n').collect();
+    let update: Vec<&str> = update.split_inclusive('\n').collect();
+
+    // Where each line of the base ended up in the other two, if it's still there
+    let current_lines = matching_lines(&base, &current);
+    let update_lines = matching_lines(&base, &update);
+
+    let mut merged = Merged {
+        contents: String::new(),
+        conflicts: 0,
+    };
+    let (mut b, mut c, mut u) = (0, 0, 0);
+    loop {
+        // The next base line that is still in both, which ends the hunk
+        let next =
+            (b..base.len()).find_map(|line| match (current_lines[line], update_lines[line]) {
+                (Some(cl), Some(ul)) if cl >= c && ul >= u => Some((line, cl, ul)),
+                _ => None,
+            });
+        let (nb, nc, nu) = next.unwrap_or((base.len(), current.len(), update.len()));
+
+        if (nb, nc, nu) == (b, c, u) && nb < base.len() {
+            merged.contents.push_str(base[b]);
+            b += 1;
+            c += 1;
+            u += 1;
+            continue;
+        }
+
+        merge_hunk(&mut merged, &base[b..nb], &current[c..nc], &update[u..nu]);
+        if next.is_none() {
+            break;
+        }
+        b = nb;
+        c = nc;
+        u = nu;
+    }
+    merged
+}
+
+/// Maps every line of `base` to its index in `other` if it wasn't changed.
+fn matching_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut lines = vec![None; base.len()];
+    let mut matcher = SequenceMatcher::new(base, other);
+    for block in matcher.get_matching_blocks() {
+        for offset in 0..block.size {
+            lines[block.first_start + offset] = Some(block.second_start + offset);
+        }
+    }
+    lines
+}
+
+fn merge_hunk(merged: &mut Merged, base: &[&str], current: &[&str], update: &[&str]) {
+    let lines = if current == base || current == update {
+        update
+    } else if update == base {
+        current
+    } else {
+        merged.conflicts += 1;
+        merged.contents.push_str(CURRENT_MARKER);
+        push_lines(&mut merged.contents, current);
+        merged.contents.push_str(SEPARATOR_MARKER);
+        push_lines(&mut merged.contents, update);
+        merged.contents.push_str(UPDATE_MARKER);
+        return;
+    };
+    for line in lines {
+        merged.contents.push_str(line);
+    }
+}
+
+/// Pushes lines between conflict markers, making sure the last one ends with a newline.
+fn push_lines(contents: &mut String, lines: &[&str]) {
+    for line in lines {
+        contents.push_str(line);
+    }
+    if !lines.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+}
+
+/// Creates a coloured unified diff of the current contents of the file against the update.
+pub(crate) fn diff(path: &str, current: &str, update: &str) -> String {
+    let current: Vec<&str> = current.split_inclusive('\n').collect();
+    let update: Vec<&str> = update.split_inclusive('\n').collect();
+
+    difflib::unified_diff(&current, &update, path, path, "current", "update", 3)
+        .iter()
+        .map(|line| {
+            let line = line.trim_end_matches('\n');
+            let colored = if line.starts_with("---") || line.starts_with("+++") {
+                line.bold()
+            } else if line.starts_with('+') {
+                line.green()
+            } else if line.starts_with('-') {
+                line.red()
+            } else if line.starts_with("@@") {
+                line.cyan()
+            } else {
+                line.normal()
+            };
+            colored.to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
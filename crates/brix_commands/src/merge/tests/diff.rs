@@ -0,0 +1,22 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use crate::merge::diff;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn unified() {
+    colored::control::set_override(false);
+    let diff = diff("out.txt", "one\ntwo\nthree\n", "one\n2\nthree");
+    assert_eq!(
+        diff,
+        "--- out.txt\tcurrent\n+++ out.txt\tupdate\n@@ -1,3 +1,3 @@\n one\n-two\n-three\n+2\n+three"
+    );
+}
+
+#[test]
+fn unchanged() {
+    assert_eq!(diff("out.txt", "same\n", "same\n"), "");
+}
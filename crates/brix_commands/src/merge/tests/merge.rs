@@ -0,0 +1,62 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use crate::merge::{merge, Merged};
+use pretty_assertions::assert_eq;
+
+const BASE: &str = "one\ntwo\nthree\nfour\n";
+
+#[test]
+fn unchanged() {
+    let merged = merge(BASE, BASE, BASE);
+    assert_eq!(
+        merged,
+        Merged {
+            contents: String::from(BASE),
+            conflicts: 0
+        }
+    );
+}
+
+#[test]
+fn takes_update() {
+    let merged = merge(BASE, BASE, "one\n2\nthree\nfour\nfive\n");
+    assert_eq!(merged.contents, "one\n2\nthree\nfour\nfive\n");
+    assert_eq!(merged.conflicts, 0);
+}
+
+#[test]
+fn keeps_current() {
+    let merged = merge(BASE, "zero\none\ntwo\nthree\n", BASE);
+    assert_eq!(merged.contents, "zero\none\ntwo\nthree\n");
+    assert_eq!(merged.conflicts, 0);
+}
+
+#[test]
+fn combines_both() {
+    let merged = merge(BASE, "one\n2\nthree\nfour\n", "one\ntwo\nthree\n4\n");
+    assert_eq!(merged.contents, "one\n2\nthree\n4\n");
+    assert_eq!(merged.conflicts, 0);
+}
+
+#[test]
+fn conflict() {
+    let merged = merge(BASE, "one\ndos\nthree\nfour\n", "one\nzwei\nthree\nfour");
+    assert_eq!(
+        merged.contents,
+        "one\n<<<<<<< current\ndos\n=======\nzwei\n>>>>>>> update\nthree\nfour"
+    );
+    assert_eq!(merged.conflicts, 1);
+}
+
+#[test]
+fn no_base() {
+    let merged = merge("", "current\n", "update");
+    assert_eq!(
+        merged.contents,
+        "<<<<<<< current\ncurrent\n=======\nupdate\n>>>>>>> update\n"
+    );
+    assert_eq!(merged.conflicts, 1);
+}
@@ -0,0 +1,72 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [CommandRegistry], which maps the names used in configs to the commands they create.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use brix_errors::BrixError;
+
+use crate::command::Command;
+
+#[cfg(test)]
+mod tests {
+    mod create;
+}
+
+/// Creates a new command, given the registry it was registered with
+/// so that commands running other configs can use the same commands.
+pub type CommandFactory = Rc<dyn Fn(&CommandRegistry) -> Box<dyn Command>>;
+
+/// Maps command names to the factories that create them.
+/// Crates embedding Brix can register their own commands alongside the built in ones.
+#[derive(Clone, Default)]
+pub struct CommandRegistry {
+    factories: BTreeMap<String, CommandFactory>,
+}
+
+impl CommandRegistry {
+    /// Creates a registry without any commands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the command under the name, replacing any command already registered with it.
+    /// Names are case insensitive.
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&CommandRegistry) -> Box<dyn Command> + 'static,
+    {
+        self.factories.insert(name.to_lowercase(), Rc::new(factory));
+    }
+
+    /// The names of every registered command in alphabetical order.
+    pub fn names(&self) -> Vec<&str> {
+        self.factories.keys().map(|name| name.as_str()).collect()
+    }
+
+    /// Returns the factory for the command, or an error with the closest registered name
+    /// if there isn't one.
+    pub fn factory(&self, name: &str) -> Result<CommandFactory, BrixError> {
+        if let Some(factory) = self.factories.get(&name.to_lowercase()) {
+            return Ok(factory.clone());
+        }
+
+        let matches = difflib::get_close_matches(name, self.names(), 1, 0.6);
+        match matches.first() {
+            Some(closest) => Err(BrixError::with(&format!(
+                "command '{}' not found... did you mean '{}'?",
+                name, closest
+            ))),
+            None => Err(BrixError::with(&format!("command '{}' not found", name))),
+        }
+    }
+
+    /// Creates the command registered under the name, see [factory](CommandRegistry::factory).
+    pub fn create(&self, name: &str) -> Result<Box<dyn Command>, BrixError> {
+        Ok(self.factory(name)?(self))
+    }
+}
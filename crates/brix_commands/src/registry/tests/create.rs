@@ -0,0 +1,32 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use crate::{CommandRegistry, MkdirCommand};
+
+fn registry() -> CommandRegistry {
+    let mut registry = CommandRegistry::new();
+    registry.register("mkdir", |_| Box::new(MkdirCommand::new()));
+    registry
+}
+
+#[test]
+fn create() {
+    let registry = registry();
+    assert_eq!(registry.names(), ["mkdir"]);
+    assert_eq!(registry.create("MKDIR").unwrap().name(), "mkdir");
+}
+
+#[test]
+fn suggestion() {
+    let registry = registry();
+    assert_eq!(
+        registry.create("mkdri").err().unwrap().message,
+        "command 'mkdri' not found... did you mean 'mkdir'?"
+    );
+    assert_eq!(
+        registry.create("template").err().unwrap().message,
+        "command 'template' not found"
+    );
+}
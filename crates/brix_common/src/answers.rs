@@ -0,0 +1,74 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [Answers], which stores the answers of every config that was run in
+//! `.brix-answers.yml` so that `brix update` can run them again.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use brix_errors::BrixError;
+
+use crate::filesystem::Filesystem;
+
+#[cfg(test)]
+mod tests {
+    mod answers;
+}
+
+/// The file answers are written to, relative to the working directory.
+pub const ANSWERS_FILE: &str = ".brix-answers.yml";
+
+/// The answers of a single config.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfigAnswers {
+    pub language: String,
+    pub config: String,
+    /// The arguments, inputs and `--set` overrides that were given.
+    pub answers: BTreeMap<String, Value>,
+    /// The context the commands were run with, for reference.
+    #[serde(default)]
+    pub context: BTreeMap<String, Value>,
+}
+
+/// Every config that was run in the working directory.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Answers {
+    #[serde(default)]
+    pub configs: Vec<ConfigAnswers>,
+}
+
+impl Answers {
+    /// Reads the answers in the working directory, which are empty if there aren't any yet.
+    pub fn read(fs: &dyn Filesystem, workdir: &Path) -> Result<Self, BrixError> {
+        let path = workdir.join(ANSWERS_FILE);
+        if !fs.exists(&path) {
+            return Ok(Self::default());
+        }
+        Ok(serde_yaml::from_str(&fs.read_to_string(&path)?)?)
+    }
+
+    /// Writes the answers to `.brix-answers.yml` in the working directory.
+    pub fn write(&self, fs: &dyn Filesystem, workdir: &Path) -> Result<(), BrixError> {
+        fs.write(
+            &workdir.join(ANSWERS_FILE),
+            serde_yaml::to_string(self)?.as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    /// Adds the answers of a config, replacing the previous answers of the same config.
+    pub fn upsert(&mut self, answers: ConfigAnswers) {
+        let existing = self.configs.iter_mut().find(|existing| {
+            existing.language == answers.language && existing.config == answers.config
+        });
+        match existing {
+            Some(existing) => *existing = answers,
+            None => self.configs.push(answers),
+        }
+    }
+}
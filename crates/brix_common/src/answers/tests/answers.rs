@@ -0,0 +1,55 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::answers::{Answers, ConfigAnswers};
+use crate::filesystem::DiskFilesystem;
+
+fn config_answers(config: &str, name: &str) -> ConfigAnswers {
+    let mut answers = BTreeMap::new();
+    answers.insert(String::from("name"), json!(name));
+    ConfigAnswers {
+        language: String::from("rust"),
+        config: String::from(config),
+        answers,
+        context: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn upsert() {
+    let mut answers = Answers::default();
+    answers.upsert(config_answers("app", "first"));
+    answers.upsert(config_answers("lib", "lib"));
+    answers.upsert(config_answers("app", "second"));
+
+    assert_eq!(
+        answers.configs,
+        vec![
+            config_answers("app", "second"),
+            config_answers("lib", "lib")
+        ]
+    );
+}
+
+#[test]
+fn write_and_read() {
+    let workdir = std::env::temp_dir().join(format!("brix-answers-{}", std::process::id()));
+    fs::create_dir_all(&workdir).unwrap();
+    assert_eq!(
+        Answers::read(&DiskFilesystem, &workdir).unwrap(),
+        Answers::default()
+    );
+
+    let mut answers = Answers::default();
+    answers.upsert(config_answers("app", "app"));
+    answers.write(&DiskFilesystem, &workdir).unwrap();
+    assert_eq!(Answers::read(&DiskFilesystem, &workdir).unwrap(), answers);
+
+    fs::remove_dir_all(&workdir).unwrap();
+}
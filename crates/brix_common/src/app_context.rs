@@ -3,11 +3,75 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use brix_cli::Config;
 use brix_processor::ProcessorCore;
 
+use crate::filesystem::{DiskFilesystem, Filesystem};
+use crate::journal::Journal;
+
 /// Struct containing common properties that require access during command execution.
 pub struct AppContext<'a> {
     pub processor: ProcessorCore<'a>,
     pub config: &'a Config,
+    /// Shared with the child contexts of the `generate` command so a failure rolls back everything.
+    pub journal: Rc<Journal>,
+    /// What commands read and write files through, the disk unless set with [with_filesystem](AppContext::with_filesystem).
+    pub fs: Rc<dyn Filesystem>,
+    /// Context exported by plugins, which is available to every command processed after them.
+    pub exports: Rc<RefCell<HashMap<String, Value>>>,
+    /// The language and name of every config running the current one with `generate`,
+    /// from the outermost one, which is used to catch configs that generate each other.
+    pub parents: Vec<(String, String)>,
+}
+
+impl<'a> AppContext<'a> {
+    /// Creates the context with an empty journal, which only records anything
+    /// if rollback isn't disabled with `--no-rollback`.
+    pub fn new(processor: ProcessorCore<'a>, config: &'a Config) -> Self {
+        Self {
+            processor,
+            config,
+            journal: Rc::new(Journal::new(!config.no_rollback)),
+            fs: Rc::new(DiskFilesystem),
+            exports: Rc::default(),
+            parents: vec![],
+        }
+    }
+
+    /// Uses the filesystem instead of the disk, for both the commands and the journal.
+    pub fn with_filesystem(mut self, fs: Rc<dyn Filesystem>) -> Self {
+        self.journal = Rc::new(Journal::with_filesystem(
+            !self.config.no_rollback,
+            fs.clone(),
+        ));
+        self.fs = fs;
+        self
+    }
+
+    /// Creates a context for another config that shares the journal, filesystem and exports of this one.
+    pub fn child<'b>(&self, processor: ProcessorCore<'b>, config: &'b Config) -> AppContext<'b> {
+        AppContext {
+            processor,
+            config,
+            journal: self.journal.clone(),
+            fs: self.fs.clone(),
+            exports: self.exports.clone(),
+            parents: self.chain(),
+        }
+    }
+
+    /// The parents along with the config of this context.
+    pub fn chain(&self) -> Vec<(String, String)> {
+        let mut chain = self.parents.clone();
+        chain.push((
+            self.config.language.clone(),
+            self.config.config_name.clone(),
+        ));
+        chain
+    }
 }
@@ -3,50 +3,182 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 
+use brix_errors::BrixError;
+
+#[cfg(test)]
+mod tests {
+    mod merge;
+    mod overrides;
+    mod positional;
+}
+
 /// Current type used for contexts.
-type StdContext = HashMap<String, String>;
+type StdContext = HashMap<String, Value>;
 
 /// Struct containing all types of contexts used in commands.
+#[derive(Debug, Clone, Default)]
 pub struct ContextMap {
     pub cli_positional: StdContext,
+    pub inputs: StdContext,
     pub config_global: StdContext,
     pub command_local: StdContext,
+    pub cli_overrides: StdContext,
 }
 
 impl ContextMap {
     /// Merges all contexts together into a single map respecting the priority of each.
+    /// Nested maps are merged key by key, any other value is replaced entirely.
     pub fn do_merge(&self) -> StdContext {
         let mut merged = StdContext::new();
         // Start with cli positional args
         for (key, value) in self.cli_positional.clone().into_iter() {
-            merged.insert(key, value);
+            merge_into(&mut merged, key, value);
+        }
+        // Answers to declared inputs override cli positional args
+        for (key, value) in self.inputs.clone().into_iter() {
+            merge_into(&mut merged, key, value);
         }
         // Global is lowest priority
         for (key, value) in self.config_global.clone().into_iter() {
-            merged.insert(key, value);
+            merge_into(&mut merged, key, value);
         }
         // Local overrides global context
         for (key, value) in self.command_local.clone().into_iter() {
-            merged.insert(key, value);
+            merge_into(&mut merged, key, value);
+        }
+        // `--set` and `--set-json` from the command line override everything
+        for (key, value) in self.cli_overrides.clone().into_iter() {
+            merge_into(&mut merged, key, value);
         }
 
         merged
     }
 }
 
-/// Creates the initial context based off of CLI parameters.
-pub fn cli_config_to_map(config: &brix_cli::Config) -> StdContext {
+/// Inserts the value into the context, merging it with the existing value if both are maps.
+pub fn merge_into(context: &mut StdContext, key: String, value: Value) {
+    match context.get_mut(&key) {
+        Some(existing) => merge_value(existing, value),
+        None => {
+            context.insert(key, value);
+        }
+    }
+}
+
+/// Recursively merges `value` into `existing`, with `value` taking priority.
+fn merge_value(existing: &mut Value, value: Value) {
+    match (existing, value) {
+        (Value::Object(existing), Value::Object(value)) => merge_map(existing, value),
+        (existing, value) => *existing = value,
+    }
+}
+
+fn merge_map(existing: &mut Map<String, Value>, value: Map<String, Value>) {
+    for (key, value) in value.into_iter() {
+        match existing.get_mut(&key) {
+            Some(current) => merge_value(current, value),
+            None => {
+                existing.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Creates the override context from `--set` and `--set-json`.
+/// Dotted keys such as `db.port` are expanded into nested maps.
+pub fn cli_overrides_to_map(config: &brix_cli::Config) -> StdContext {
+    let mut map = StdContext::new();
+    for (key, value) in config.overrides.iter() {
+        let mut parts = key.rsplit('.');
+        let mut nested = value.clone();
+        let mut root = parts.next().unwrap().to_string();
+        for part in parts {
+            let mut object = Map::new();
+            object.insert(root, nested);
+            nested = Value::Object(object);
+            root = part.to_string();
+        }
+        merge_into(&mut map, root, nested);
+    }
+    map
+}
+
+/// A positional argument declared by a config, in the order it's passed on the command line.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Positional {
+    pub name: String,
+    pub required: Option<bool>,
+    pub default: Option<String>,
+}
+
+impl Positional {
+    /// Positional arguments are required unless they declare a default.
+    pub fn is_required(&self) -> bool {
+        self.required.unwrap_or(self.default.is_none())
+    }
+
+    /// The positional arguments used when a config doesn't declare any, `project` and `module`.
+    pub fn defaults() -> Vec<Positional> {
+        vec![
+            Positional {
+                name: String::from("project"),
+                required: Some(true),
+                default: None,
+            },
+            Positional {
+                name: String::from("module"),
+                required: Some(true),
+                default: None,
+            },
+        ]
+    }
+}
+
+/// Whether the key is set with `--set` or `--set-json`.
+fn is_overridden(config: &brix_cli::Config, key: &str) -> bool {
+    config.overrides.iter().any(|(name, _)| name == key)
+}
+
+/// Creates the initial context based off of CLI parameters,
+/// mapping the trailing CLI arguments onto the declared positional arguments.
+pub fn cli_config_to_map(
+    config: &brix_cli::Config,
+    positionals: &[Positional],
+) -> Result<StdContext, BrixError> {
     macro_rules! s {
         ($st:expr) => {
             String::from($st)
         };
     }
 
+    if config.args.len() > positionals.len() {
+        return Err(BrixError::with(&format!(
+            "expected at most {} argument(s) but got {}, unexpected '{}'",
+            positionals.len(),
+            config.args.len(),
+            config.args[positionals.len()..].join(" ")
+        )));
+    }
+
     let mut map = HashMap::new();
-    map.insert(s!("language"), s!(&config.language));
-    map.insert(s!("module"), s!(&config.module));
-    map.insert(s!("project"), s!(&config.project));
-    map
+    map.insert(s!("language"), Value::from(s!(&config.language)));
+    for (index, positional) in positionals.iter().enumerate() {
+        let value = match config.args.get(index).or(positional.default.as_ref()) {
+            Some(value) => value,
+            // Required arguments can also be set with `--set`
+            None if positional.is_required() && !is_overridden(config, &positional.name) => {
+                return Err(BrixError::with(&format!(
+                    "missing required argument '{}'",
+                    positional.name
+                )))
+            }
+            None => continue,
+        };
+        map.insert(positional.name.clone(), Value::from(s!(value)));
+    }
+    Ok(map)
 }
@@ -0,0 +1,85 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::context::ContextMap;
+
+macro_rules! context {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut map: HashMap<String, Value> = HashMap::new();
+        $(map.insert(String::from($key), $value);)*
+        map
+    }};
+}
+
+#[test]
+fn priority() {
+    let map = ContextMap {
+        cli_positional: context! {"project" => json!("cli"), "module" => json!("cli")},
+        inputs: context! {"module" => json!("input"), "with_tests" => json!(true)},
+        config_global: context! {"project" => json!("global"), "edition" => json!(2021)},
+        command_local: context! {"edition" => json!(2022)},
+        cli_overrides: context! {},
+    };
+    let merged = map.do_merge();
+
+    assert_eq!(merged["project"], json!("global"));
+    assert_eq!(merged["module"], json!("input"));
+    assert_eq!(merged["with_tests"], json!(true));
+    assert_eq!(merged["edition"], json!(2022));
+}
+
+#[test]
+fn nested() {
+    let map = ContextMap {
+        cli_positional: context! {},
+        inputs: context! {},
+        config_global: context! {
+            "db" => json!({"host": "localhost", "port": 5432, "options": {"ssl": false}})
+        },
+        command_local: context! {
+            "db" => json!({"port": 6543, "options": {"timeout": 10}})
+        },
+        cli_overrides: context! {},
+    };
+    let merged = map.do_merge();
+
+    assert_eq!(
+        merged["db"],
+        json!({"host": "localhost", "port": 6543, "options": {"ssl": false, "timeout": 10}})
+    );
+}
+
+#[test]
+fn lists_are_replaced() {
+    let map = ContextMap {
+        cli_positional: context! {},
+        inputs: context! {},
+        config_global: context! {"items" => json!(["a", "b"])},
+        command_local: context! {"items" => json!(["c"])},
+        cli_overrides: context! {},
+    };
+    let merged = map.do_merge();
+
+    assert_eq!(merged["items"], json!(["c"]));
+}
+
+#[test]
+fn overrides() {
+    let map = ContextMap {
+        cli_positional: context! {"project" => json!("cli")},
+        inputs: context! {},
+        config_global: context! {"db" => json!({"host": "localhost", "port": 5432})},
+        command_local: context! {"project" => json!("local")},
+        cli_overrides: context! {"project" => json!("set"), "db" => json!({"port": 6543})},
+    };
+    let merged = map.do_merge();
+
+    assert_eq!(merged["project"], json!("set"));
+    assert_eq!(merged["db"], json!({"host": "localhost", "port": 6543}));
+}
@@ -0,0 +1,49 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+
+use crate::context::cli_overrides_to_map;
+
+macro_rules! config {
+    ($($key:expr => $value:expr),*) => {
+        brix_cli::Config {
+            overrides: vec![$((String::from($key), $value)),*],
+            ..brix_cli::Config::default()
+        }
+    };
+}
+
+#[test]
+fn flat() {
+    let map = cli_overrides_to_map(&config!("name" => json!("foo"), "port" => json!(8080)));
+    assert_eq!(map["name"], json!("foo"));
+    assert_eq!(map["port"], json!(8080));
+}
+
+#[test]
+fn dotted() {
+    let map = cli_overrides_to_map(&config!(
+        "db.host" => json!("localhost"),
+        "db.options.ssl" => json!(true),
+        "db.options.timeout" => json!(10)
+    ));
+    assert_eq!(
+        map["db"],
+        json!({"host": "localhost", "options": {"ssl": true, "timeout": 10}})
+    );
+}
+
+#[test]
+fn last_wins() {
+    let map = cli_overrides_to_map(&config!(
+        "db" => json!({"host": "localhost"}),
+        "db.host" => Value::from("remote"),
+        "name" => json!("foo"),
+        "name" => json!("bar")
+    ));
+    assert_eq!(map["db"], json!({"host": "remote"}));
+    assert_eq!(map["name"], json!("bar"));
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+
+use crate::context::{cli_config_to_map, Positional};
+
+macro_rules! config {
+    ($($arg:expr),*) => {
+        brix_cli::Config {
+            language: String::from("rust"),
+            args: vec![$(String::from($arg)),*],
+            ..brix_cli::Config::default()
+        }
+    };
+}
+
+fn positional(name: &str, required: Option<bool>, default: Option<&str>) -> Positional {
+    Positional {
+        name: String::from(name),
+        required,
+        default: default.map(String::from),
+    }
+}
+
+#[test]
+fn defaults() {
+    let map = cli_config_to_map(&config!("brix", "foo"), &Positional::defaults()).unwrap();
+    assert_eq!(map["language"], json!("rust"));
+    assert_eq!(map["project"], json!("brix"));
+    assert_eq!(map["module"], json!("foo"));
+
+    let err = cli_config_to_map(&config!("brix"), &Positional::defaults()).unwrap_err();
+    assert_eq!(err.message, "missing required argument 'module'");
+}
+
+#[test]
+fn declared() {
+    let positionals = vec![
+        positional("entity", None, None),
+        positional("package", None, Some("com.example")),
+        positional("suffix", Some(false), None),
+    ];
+
+    let map = cli_config_to_map(&config!("User"), &positionals).unwrap();
+    assert_eq!(map["entity"], json!("User"));
+    assert_eq!(map["package"], json!("com.example"));
+    assert!(!map.contains_key("suffix"));
+
+    let map = cli_config_to_map(&config!("User", "org.acme", "Dto"), &positionals).unwrap();
+    assert_eq!(map["package"], json!("org.acme"));
+    assert_eq!(map["suffix"], json!("Dto"));
+}
+
+#[test]
+fn none_declared() {
+    let map = cli_config_to_map(&config!(), &[]).unwrap();
+    assert_eq!(map.len(), 1);
+
+    let err = cli_config_to_map(&config!("extra", "args"), &[]).unwrap_err();
+    assert_eq!(
+        err.message,
+        "expected at most 0 argument(s) but got 2, unexpected 'extra args'"
+    );
+}
+
+#[test]
+fn set_required() {
+    let mut config = config!("brix");
+    config.overrides = vec![(String::from("module"), json!("foo"))];
+
+    let map = cli_config_to_map(&config, &Positional::defaults()).unwrap();
+    assert_eq!(map["project"], json!("brix"));
+    assert!(!map.contains_key("module"));
+}
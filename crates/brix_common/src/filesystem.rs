@@ -0,0 +1,487 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains the [Filesystem] trait that commands read and write files through,
+//! with [DiskFilesystem] for the real disk and [MemoryFilesystem] for rendering in memory.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(test)]
+mod tests {
+    mod memory;
+}
+
+/// The file access commands go through, mirroring the functions of `std::fs` they need.
+pub trait Filesystem: Debug {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    /// The paths of everything directly inside of the directory, in alphabetical order.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    /// Removes the directory, which must be empty.
+    fn remove_dir(&self, path: &Path) -> io::Result<()>;
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    /// Creates a link at `link` pointing to `target`, which is relative to the directory of the link
+    /// unless it's absolute.
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()>;
+    /// Where the link points to, as it was given when creating it.
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+    /// Whether the path is a link, without following it like the other functions do.
+    fn is_symlink(&self, path: &Path) -> bool;
+    /// The path with every link along it resolved. Unlike `std::fs::canonicalize`, the path doesn't
+    /// have to exist, and whatever part of it is missing is kept as it is.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+    fn is_file(&self, path: &Path) -> bool;
+    fn is_dir(&self, path: &Path) -> bool;
+
+    fn exists(&self, path: &Path) -> bool {
+        self.is_file(path) || self.is_dir(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.write(to, &self.read(from)?)
+    }
+
+    /// Every file inside of the directory and its subdirectories, in alphabetical order.
+    /// Links are listed like files instead of being followed, so nothing outside of the directory
+    /// is reached through them.
+    fn walk(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for path in self.read_dir(dir)? {
+            if self.is_dir(&path) && !self.is_symlink(&path) {
+                files.extend(self.walk(&path)?);
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// Reads and writes the real disk with `std::fs`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DiskFilesystem;
+
+impl Filesystem for DiskFilesystem {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut paths = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir_all(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    #[cfg(unix)]
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        std::os::unix::fs::symlink(target, link)
+    }
+
+    #[cfg(windows)]
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        let resolved = link.parent().unwrap_or(Path::new("")).join(target);
+        match resolved.is_dir() {
+            true => std::os::windows::fs::symlink_dir(target, link),
+            false => std::os::windows::fs::symlink_file(target, link),
+        }
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::read_link(path)
+    }
+
+    fn is_symlink(&self, path: &Path) -> bool {
+        fs::symlink_metadata(path)
+            .map(|metadata| metadata.file_type().is_symlink())
+            .unwrap_or(false)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        let mut existing = normalize(path);
+        let mut missing = Vec::new();
+        loop {
+            if let Ok(real) = fs::canonicalize(&existing) {
+                return Ok(missing
+                    .iter()
+                    .rev()
+                    .fold(real, |real, name| real.join(name)));
+            }
+            match (existing.file_name(), existing.parent()) {
+                (Some(name), Some(parent)) => {
+                    missing.push(name.to_os_string());
+                    existing = parent.to_path_buf();
+                }
+                _ => return Ok(normalize(path)),
+            }
+        }
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+}
+
+/// A path in a [MemoryFilesystem].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Entry {
+    File(Vec<u8>),
+    Dir,
+    /// A link to the path, as it was given.
+    Symlink(PathBuf),
+    /// Hides whatever is on the disk at the path.
+    Removed,
+}
+
+/// Keeps every file in memory, so a config can be rendered without changing anything on the disk.
+/// Unlike the disk, the parent directories of a file are created when writing it.
+#[derive(Debug, Default)]
+pub struct MemoryFilesystem {
+    entries: RefCell<BTreeMap<PathBuf, Entry>>,
+    read_through: bool,
+}
+
+impl MemoryFilesystem {
+    /// Creates an empty filesystem.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a filesystem that reads from the disk whatever hasn't been written or removed in memory,
+    /// such as the templates of a config, while every change stays in memory.
+    pub fn over_disk() -> Self {
+        Self {
+            entries: RefCell::default(),
+            read_through: true,
+        }
+    }
+
+    /// Every file that was written, along with its contents.
+    pub fn files(&self) -> BTreeMap<PathBuf, Vec<u8>> {
+        self.entries
+            .borrow()
+            .iter()
+            .filter_map(|(path, entry)| match entry {
+                Entry::File(contents) => Some((path.clone(), contents.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The entry in memory for the path, or `Removed` if it's inside of a removed directory.
+    fn entry(&self, path: &Path) -> Option<Entry> {
+        let entries = self.entries.borrow();
+        if let Some(entry) = entries.get(path) {
+            return Some(entry.clone());
+        }
+        if path
+            .ancestors()
+            .skip(1)
+            .any(|ancestor| entries.get(ancestor) == Some(&Entry::Removed))
+        {
+            return Some(Entry::Removed);
+        }
+        // Directories that files were written to without creating them first
+        let has_children = entries
+            .iter()
+            .any(|(other, entry)| entry != &Entry::Removed && other.starts_with(path));
+        match has_children {
+            true => Some(Entry::Dir),
+            false => None,
+        }
+    }
+}
+
+/// Removes the `.` and resolves the `..` of the path without following links,
+/// since `dir!` leaves them in and the same file has to be found however it's written.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Where the link points to, relative to the directory it's in.
+fn link_target(link: &Path, target: &Path) -> PathBuf {
+    normalize(&link.parent().unwrap_or_else(|| Path::new("")).join(target))
+}
+
+/// How many links are followed before giving up, like the limit of Linux.
+const MAX_LINKS: usize = 40;
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        ErrorKind::NotFound,
+        format!("'{}' does not exist", path.display()),
+    )
+}
+
+impl Filesystem for MemoryFilesystem {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let path = &normalize(path);
+        match self.entry(path) {
+            Some(Entry::File(contents)) => Ok(contents),
+            Some(Entry::Symlink(target)) => self.read(&link_target(path, &target)),
+            None if self.read_through => fs::read(path),
+            _ => Err(not_found(path)),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let path = &normalize(path);
+        if self.is_dir(path) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("'{}' is a directory", path.display()),
+            ));
+        }
+        // The parents stay around like on the disk, even once the file is removed
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent)?;
+        }
+        self.entries
+            .borrow_mut()
+            .insert(path.to_path_buf(), Entry::File(contents.to_vec()));
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        let path = &normalize(path);
+        for ancestor in path.ancestors() {
+            if ancestor.as_os_str().is_empty() || self.is_dir(ancestor) {
+                break;
+            }
+            if self.is_file(ancestor) {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("'{}' is a file", ancestor.display()),
+                ));
+            }
+            self.entries
+                .borrow_mut()
+                .insert(ancestor.to_path_buf(), Entry::Dir);
+        }
+        Ok(())
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let path = &normalize(path);
+        if !self.is_dir(path) {
+            return Err(not_found(path));
+        }
+
+        let mut children = BTreeSet::new();
+        for (other, entry) in self.entries.borrow().iter() {
+            if entry == &Entry::Removed || other == path {
+                continue;
+            }
+            if let Ok(rest) = other.strip_prefix(path) {
+                if let Some(first) = rest.components().next() {
+                    children.insert(path.join(first));
+                }
+            }
+        }
+        if self.read_through && path.is_dir() {
+            for entry in fs::read_dir(path)? {
+                let child = entry?.path();
+                if self.exists(&child) {
+                    children.insert(child);
+                }
+            }
+        }
+        Ok(children.into_iter().collect())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let path = &normalize(path);
+        if !self.is_file(path) && !self.is_symlink(path) {
+            return Err(not_found(path));
+        }
+        self.entries
+            .borrow_mut()
+            .insert(path.to_path_buf(), Entry::Removed);
+        Ok(())
+    }
+
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        let path = &normalize(path);
+        if !self.read_dir(path)?.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("'{}' is not empty", path.display()),
+            ));
+        }
+        self.entries
+            .borrow_mut()
+            .insert(path.to_path_buf(), Entry::Removed);
+        Ok(())
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        let path = &normalize(path);
+        if !self.is_dir(path) {
+            return Err(not_found(path));
+        }
+        let mut entries = self.entries.borrow_mut();
+        entries.retain(|other, _| !other.starts_with(path));
+        entries.insert(path.to_path_buf(), Entry::Removed);
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let from = &normalize(from);
+        let to = &normalize(to);
+        if self.is_symlink(from) {
+            self.symlink(&self.read_link(from)?, to)?;
+            return self.remove_file(from);
+        }
+        if self.is_file(from) {
+            self.write(to, &self.read(from)?)?;
+            return self.remove_file(from);
+        }
+
+        for file in self.walk(from)? {
+            let destination = to.join(file.strip_prefix(from).unwrap());
+            match self.is_symlink(&file) {
+                true => self.symlink(&self.read_link(&file)?, &destination)?,
+                false => self.write(&destination, &self.read(&file)?)?,
+            }
+        }
+        self.create_dir_all(to)?;
+        self.remove_dir_all(from)
+    }
+
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        let link = &normalize(link);
+        if self.exists(link) || self.is_symlink(link) {
+            return Err(io::Error::new(
+                ErrorKind::AlreadyExists,
+                format!("'{}' already exists", link.display()),
+            ));
+        }
+        self.entries
+            .borrow_mut()
+            .insert(link.to_path_buf(), Entry::Symlink(target.to_path_buf()));
+        Ok(())
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        let path = &normalize(path);
+        match self.entry(path) {
+            Some(Entry::Symlink(target)) => Ok(target),
+            None if self.read_through => fs::read_link(path),
+            _ => Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("'{}' is not a link", path.display()),
+            )),
+        }
+    }
+
+    fn is_symlink(&self, path: &Path) -> bool {
+        let path = &normalize(path);
+        match self.entry(path) {
+            Some(entry) => matches!(entry, Entry::Symlink(_)),
+            None => self.read_through && DiskFilesystem.is_symlink(path),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        let mut path = normalize(path);
+        let mut links = 0;
+        // Resolves the first link along the path and starts over, until there are none left
+        'resolve: loop {
+            let mut resolved = PathBuf::new();
+            for component in path.components() {
+                resolved.push(component);
+                if self.is_symlink(&resolved) {
+                    links += 1;
+                    if links > MAX_LINKS {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!("too many levels of links in '{}'", path.display()),
+                        ));
+                    }
+                    let rest = path.strip_prefix(&resolved).unwrap().to_path_buf();
+                    path =
+                        normalize(&link_target(&resolved, &self.read_link(&resolved)?).join(rest));
+                    continue 'resolve;
+                }
+            }
+            return Ok(path);
+        }
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        let path = &normalize(path);
+        match self.entry(path) {
+            Some(Entry::Symlink(target)) => self.is_file(&link_target(path, &target)),
+            Some(entry) => matches!(entry, Entry::File(_)),
+            None => self.read_through && path.is_file(),
+        }
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        let path = &normalize(path);
+        match self.entry(path) {
+            Some(Entry::Symlink(target)) => self.is_dir(&link_target(path, &target)),
+            Some(entry) => entry == Entry::Dir,
+            None => self.read_through && path.is_dir(),
+        }
+    }
+}
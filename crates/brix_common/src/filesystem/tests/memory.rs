@@ -0,0 +1,89 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::path::{Path, PathBuf};
+
+use crate::filesystem::{Filesystem, MemoryFilesystem};
+
+#[test]
+fn write_and_read() {
+    let fs = MemoryFilesystem::new();
+    fs.write(Path::new("out/a/one.txt"), b"one").unwrap();
+    fs.write(Path::new("out/two.txt"), b"two").unwrap();
+
+    assert_eq!(fs.read(Path::new("out/a/one.txt")).unwrap(), b"one");
+    assert!(fs.is_file(Path::new("out/two.txt")));
+    assert!(fs.is_dir(Path::new("out/a")));
+    assert!(!fs.exists(Path::new("out/three.txt")));
+    assert_eq!(
+        fs.read_dir(Path::new("out")).unwrap(),
+        vec![PathBuf::from("out/a"), PathBuf::from("out/two.txt")]
+    );
+    assert_eq!(
+        fs.walk(Path::new("out")).unwrap(),
+        vec![PathBuf::from("out/a/one.txt"), PathBuf::from("out/two.txt")]
+    );
+}
+
+#[test]
+fn remove_and_rename() {
+    let fs = MemoryFilesystem::new();
+    fs.write(Path::new("out/a/one.txt"), b"one").unwrap();
+    fs.write(Path::new("out/two.txt"), b"two").unwrap();
+
+    fs.rename(Path::new("out/a"), Path::new("out/b")).unwrap();
+    assert!(!fs.exists(Path::new("out/a")));
+    assert_eq!(fs.read(Path::new("out/b/one.txt")).unwrap(), b"one");
+
+    fs.remove_file(Path::new("out/two.txt")).unwrap();
+    assert!(fs.remove_dir(Path::new("out")).is_err());
+    fs.remove_dir_all(Path::new("out")).unwrap();
+    assert!(!fs.exists(Path::new("out/b/one.txt")));
+    assert!(fs.files().is_empty());
+}
+
+#[test]
+fn over_disk() {
+    let fs = MemoryFilesystem::over_disk();
+    let dir = Path::new("src/filesystem/tests");
+    let file = dir.join("memory.rs");
+
+    // Reads come from the disk until the path is changed in memory
+    assert!(fs.is_dir(dir));
+    assert!(fs.read_to_string(&file).unwrap().contains("fn over_disk()"));
+    fs.write(&file, b"changed").unwrap();
+    assert_eq!(fs.read(&file).unwrap(), b"changed");
+    fs.remove_dir_all(dir).unwrap();
+    assert!(!fs.exists(&file));
+    assert!(file.is_file());
+}
+
+#[test]
+fn symlinks() {
+    let fs = MemoryFilesystem::new();
+    fs.write(Path::new("/outside/secret.txt"), b"secret")
+        .unwrap();
+    fs.write(Path::new("/work/app/one.txt"), b"one").unwrap();
+    fs.symlink(Path::new("../../outside"), Path::new("/work/app/shared"))
+        .unwrap();
+
+    // Links aren't followed out of the directory
+    assert_eq!(
+        fs.walk(Path::new("/work")).unwrap(),
+        vec![
+            PathBuf::from("/work/app/one.txt"),
+            PathBuf::from("/work/app/shared")
+        ]
+    );
+    assert_eq!(
+        fs.canonicalize(Path::new("/work/app/shared/new/file.txt"))
+            .unwrap(),
+        PathBuf::from("/outside/new/file.txt")
+    );
+
+    fs.symlink(Path::new("loop"), Path::new("/work/loop"))
+        .unwrap();
+    assert!(fs.canonicalize(Path::new("/work/loop/file.txt")).is_err());
+}
@@ -0,0 +1,346 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains the [Manifest] written to `.brix/history` after every run, which `brix undo` uses
+//! to delete the files a run created and restore the files it modified.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use brix_errors::BrixError;
+
+use crate::filesystem::Filesystem;
+use crate::journal::{Change, Journal};
+
+#[cfg(test)]
+mod tests {
+    mod render;
+    mod undo;
+}
+
+/// The directory manifests are written to, relative to the working directory.
+pub const HISTORY_DIR: &str = ".brix/history";
+/// The directory the original contents of modified files are stored in, named by their hash.
+const OBJECTS_DIR: &str = "objects";
+
+/// Whether a path was created or modified by the run.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Created,
+    Modified,
+    /// Changed by a plugin without knowing what was there before, which undo leaves alone.
+    Touched,
+}
+
+/// A single path changed by the run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// The path, relative to the working directory if it's inside of it.
+    pub path: PathBuf,
+    pub status: FileStatus,
+    /// The hash of the contents after the run, or `None` for directories.
+    pub hash: Option<String>,
+    /// The hash of the contents before the run for modified files.
+    pub original: Option<String>,
+    /// The hash of what was rendered for the file, which `brix update` merges against.
+    #[serde(default)]
+    pub render: Option<String>,
+    /// Where the path pointed to before the run if it was a link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<PathBuf>,
+}
+
+/// The record of a single run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Manifest {
+    /// Milliseconds since the unix epoch, also used as the file name.
+    pub timestamp: u128,
+    pub language: String,
+    pub config: String,
+    pub context: HashMap<String, Value>,
+    pub files: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Creates the manifest from every change recorded in the journal,
+    /// storing the original contents of modified files so they can be restored
+    /// and what was rendered for each file so it can be updated.
+    pub fn record(
+        fs: &dyn Filesystem,
+        config: &brix_cli::Config,
+        context: HashMap<String, Value>,
+        journal: &Journal,
+    ) -> Result<Self, BrixError> {
+        let workdir = &config.workdir;
+        let objects = workdir.join(HISTORY_DIR).join(OBJECTS_DIR);
+        let mut files = Vec::new();
+
+        for change in journal.changes() {
+            let path = change.path();
+            let hash = match fs.is_file(path) {
+                true => Some(content_hash(&fs.read(path)?)),
+                false => None,
+            };
+            let (status, original, link) = match &change {
+                Change::Created(_) => (FileStatus::Created, None, None),
+                Change::Modified { original, .. } => (
+                    FileStatus::Modified,
+                    Some(write_object(fs, &objects, original)?),
+                    None,
+                ),
+                Change::Linked { target, .. } => (FileStatus::Modified, None, Some(target.clone())),
+                Change::Touched(_) => (FileStatus::Touched, None, None),
+            };
+            let render = match journal.render(path) {
+                Some(render) => Some(write_object(fs, &objects, &render)?),
+                None => None,
+            };
+
+            files.push(ManifestEntry {
+                path: path.strip_prefix(workdir).unwrap_or(path).to_path_buf(),
+                status,
+                hash,
+                original,
+                render,
+                link,
+            });
+        }
+
+        Ok(Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis())
+                .unwrap_or_default(),
+            language: config.language.clone(),
+            config: config.config_name.clone(),
+            context,
+            files,
+        })
+    }
+
+    /// Writes the manifest to `.brix/history/<timestamp>.json` and returns its path.
+    pub fn write(&self, fs: &dyn Filesystem, workdir: &Path) -> Result<PathBuf, BrixError> {
+        let dir = workdir.join(HISTORY_DIR);
+        fs.create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.json", self.timestamp));
+        fs.write(&path, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(path)
+    }
+
+    /// Reads the most recent manifest in the working directory, if there is one.
+    pub fn latest(
+        fs: &dyn Filesystem,
+        workdir: &Path,
+    ) -> Result<Option<(PathBuf, Self)>, BrixError> {
+        match manifest_paths(fs, workdir)?.pop() {
+            Some(path) => {
+                let manifest = serde_json::from_str(&fs.read_to_string(&path)?)?;
+                Ok(Some((path, manifest)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Finds what the most recent run that rendered the file rendered for it.
+    /// `path` is relative to the working directory.
+    pub fn last_render(
+        fs: &dyn Filesystem,
+        workdir: &Path,
+        path: &Path,
+    ) -> Result<Option<Vec<u8>>, BrixError> {
+        let objects = workdir.join(HISTORY_DIR).join(OBJECTS_DIR);
+        let render = Self::entries(fs, workdir, path)?
+            .into_iter()
+            .find_map(|entry| entry.render);
+        match render {
+            Some(render) => Ok(Some(fs.read(&objects.join(render))?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The hash of what the most recent run that wrote to the file left in it.
+    /// `path` is relative to the working directory.
+    pub fn last_hash(
+        fs: &dyn Filesystem,
+        workdir: &Path,
+        path: &Path,
+    ) -> Result<Option<String>, BrixError> {
+        Ok(Self::entries(fs, workdir, path)?
+            .into_iter()
+            .find_map(|entry| entry.hash))
+    }
+
+    /// Every entry for the path, from the newest run to the oldest.
+    fn entries(
+        fs: &dyn Filesystem,
+        workdir: &Path,
+        path: &Path,
+    ) -> Result<Vec<ManifestEntry>, BrixError> {
+        let mut entries = Vec::new();
+        for manifest_path in manifest_paths(fs, workdir)?.into_iter().rev() {
+            let manifest: Self = serde_json::from_str(&fs.read_to_string(&manifest_path)?)?;
+            entries.extend(
+                manifest
+                    .files
+                    .into_iter()
+                    .filter(|entry| entry.path == path),
+            );
+        }
+        Ok(entries)
+    }
+
+    /// Deletes the files that were created and restores the files that were modified or removed,
+    /// returning every path that was undone. Refuses to undo anything if a file
+    /// was changed since the run, unless `force` is true. Files touched by plugins are left alone.
+    pub fn undo(
+        &self,
+        fs: &dyn Filesystem,
+        workdir: &Path,
+        force: bool,
+    ) -> Result<Vec<PathBuf>, BrixError> {
+        let files: Vec<&ManifestEntry> = self
+            .files
+            .iter()
+            .filter(|entry| entry.status != FileStatus::Touched)
+            .collect();
+        let changed: Vec<String> = files
+            .iter()
+            .filter(|entry| entry.is_file() && !entry.is_unchanged(fs, workdir))
+            .map(|entry| entry.path.display().to_string())
+            .collect();
+        if !changed.is_empty() && !force {
+            return Err(BrixError::with(&format!(
+                "refusing to undo, these files were changed since they were generated: {}\nuse --force to undo anyway",
+                changed.join(", ")
+            )));
+        }
+
+        let objects = workdir.join(HISTORY_DIR).join(OBJECTS_DIR);
+        let mut undone = Vec::new();
+        for entry in files.iter().filter(|entry| entry.is_file()) {
+            let path = workdir.join(&entry.path);
+            match (entry.status, &entry.original) {
+                (FileStatus::Modified, _) if entry.link.is_some() => {
+                    if fs.is_symlink(&path) || fs.is_file(&path) {
+                        fs.remove_file(&path)?;
+                    }
+                    if let Some(parent) = path.parent() {
+                        fs.create_dir_all(parent)?;
+                    }
+                    fs.symlink(entry.link.as_ref().unwrap(), &path)?;
+                }
+                (FileStatus::Modified, Some(original)) => {
+                    if let Some(parent) = path.parent() {
+                        fs.create_dir_all(parent)?;
+                    }
+                    fs.write(&path, &fs.read(&objects.join(original))?)?;
+                }
+                _ if fs.is_file(&path) || fs.is_symlink(&path) => fs.remove_file(&path)?,
+                _ => continue,
+            }
+            undone.push(entry.path.clone());
+        }
+
+        // Created directories are only removed once nothing else is left in them,
+        // starting with the deepest ones
+        let mut directories: Vec<&ManifestEntry> = files
+            .into_iter()
+            .filter(|entry| !entry.is_file() && entry.status == FileStatus::Created)
+            .collect();
+        directories.sort_by_key(|entry| std::cmp::Reverse(entry.path.components().count()));
+        for entry in directories {
+            if remove_empty_dirs(fs, &workdir.join(&entry.path))? {
+                undone.push(entry.path.clone());
+            }
+        }
+
+        Ok(undone)
+    }
+}
+
+impl ManifestEntry {
+    /// Whether the entry is a file, which is either there after the run or was removed by it.
+    fn is_file(&self) -> bool {
+        self.hash.is_some() || self.status == FileStatus::Modified
+    }
+
+    /// Whether the file still has the contents it had after the run.
+    fn is_unchanged(&self, fs: &dyn Filesystem, workdir: &Path) -> bool {
+        let path = workdir.join(&self.path);
+        match (self.status, fs.read(&path)) {
+            (_, Ok(contents)) => Some(content_hash(&contents)) == self.hash,
+            // A created file that has already been deleted has nothing left to undo
+            (FileStatus::Created, Err(_)) => true,
+            // A file that was removed by the run has to still be gone
+            (FileStatus::Modified, Err(_)) => self.hash.is_none(),
+            (FileStatus::Touched, Err(_)) => true,
+        }
+    }
+}
+
+/// Every manifest in the working directory, from oldest to newest.
+fn manifest_paths(fs: &dyn Filesystem, workdir: &Path) -> Result<Vec<PathBuf>, BrixError> {
+    let dir = workdir.join(HISTORY_DIR);
+    if !fs.exists(&dir) {
+        return Ok(vec![]);
+    }
+
+    let mut manifests: Vec<(u128, PathBuf)> = fs
+        .read_dir(&dir)?
+        .into_iter()
+        .filter_map(|path| {
+            let timestamp = path.file_stem()?.to_str()?.parse::<u128>().ok()?;
+            match path.extension()? == "json" {
+                true => Some((timestamp, path)),
+                false => None,
+            }
+        })
+        .collect();
+    manifests.sort_by_key(|(timestamp, _)| *timestamp);
+    Ok(manifests.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Stores the contents in the objects directory and returns their hash.
+fn write_object(fs: &dyn Filesystem, objects: &Path, contents: &[u8]) -> Result<String, BrixError> {
+    let hash = content_hash(contents);
+    fs.create_dir_all(objects)?;
+    fs.write(&objects.join(&hash), contents)?;
+    Ok(hash)
+}
+
+/// Removes the directory and every directory inside of it, as long as they don't contain any files.
+/// Returns whether the directory was removed.
+fn remove_empty_dirs(fs: &dyn Filesystem, dir: &Path) -> Result<bool, BrixError> {
+    if !fs.is_dir(dir) {
+        return Ok(false);
+    }
+
+    let mut empty = true;
+    for path in fs.read_dir(dir)? {
+        if !(fs.is_dir(&path) && remove_empty_dirs(fs, &path)?) {
+            empty = false;
+        }
+    }
+
+    if empty {
+        fs.remove_dir(dir)?;
+    }
+    Ok(empty)
+}
+
+/// Hashes file contents to tell whether they have changed, using 64 bit FNV-1a.
+/// Only meant for detecting changes, not for anything security related.
+pub fn content_hash(contents: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in contents {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,55 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::filesystem::DiskFilesystem;
+use crate::history::Manifest;
+use crate::journal::Journal;
+
+fn workdir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("brix-history-render-{}", std::process::id()));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn render(workdir: &Path, timestamp: u128, file: &str, contents: &str) {
+    let config = brix_cli::Config {
+        workdir: workdir.to_path_buf(),
+        ..brix_cli::Config::default()
+    };
+    let path = workdir.join(file);
+    let journal = Journal::new(true);
+    journal.record(&path).unwrap();
+    fs::write(&path, contents).unwrap();
+    journal.record_render(&path, contents.as_bytes().to_vec());
+
+    let mut manifest =
+        Manifest::record(&DiskFilesystem, &config, HashMap::new(), &journal).unwrap();
+    manifest.timestamp = timestamp;
+    manifest.write(&DiskFilesystem, workdir).unwrap();
+}
+
+#[test]
+fn last_render() {
+    let workdir = workdir();
+    render(&workdir, 1, "a.txt", "first");
+    render(&workdir, 2, "a.txt", "second");
+    render(&workdir, 3, "b.txt", "other");
+
+    assert_eq!(
+        Manifest::last_render(&DiskFilesystem, &workdir, Path::new("a.txt")).unwrap(),
+        Some(b"second".to_vec())
+    );
+    assert_eq!(
+        Manifest::last_render(&DiskFilesystem, &workdir, Path::new("c.txt")).unwrap(),
+        None
+    );
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::filesystem::{DiskFilesystem, Filesystem, MemoryFilesystem};
+use crate::history::{content_hash, FileStatus, Manifest};
+use crate::journal::Journal;
+
+fn workdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("brix-history-{}-{}", name, std::process::id()));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Simulates a run that modifies `existing.txt` and creates `src/main.rs`.
+fn generate(workdir: &Path) -> Manifest {
+    let config = brix_cli::Config {
+        language: String::from("rust"),
+        config_name: String::from("app"),
+        workdir: workdir.to_path_buf(),
+        ..brix_cli::Config::default()
+    };
+    let existing = workdir.join("existing.txt");
+    fs::write(&existing, "original").unwrap();
+
+    let journal = Journal::new(true);
+    journal.record(&existing).unwrap();
+    fs::write(&existing, "generated").unwrap();
+    let created = workdir.join("src/main.rs");
+    journal.record(&created).unwrap();
+    fs::create_dir_all(created.parent().unwrap()).unwrap();
+    fs::write(&created, "fn main() {}").unwrap();
+
+    let mut context = HashMap::new();
+    context.insert(String::from("project"), json!("app"));
+    let manifest = Manifest::record(&DiskFilesystem, &config, context, &journal).unwrap();
+    manifest.write(&DiskFilesystem, workdir).unwrap();
+    manifest
+}
+
+#[test]
+fn record() {
+    let workdir = workdir("record");
+    let manifest = generate(&workdir);
+
+    assert_eq!(manifest.language, "rust");
+    assert_eq!(manifest.config, "app");
+    assert_eq!(manifest.context["project"], json!("app"));
+    let paths: Vec<(PathBuf, FileStatus)> = manifest
+        .files
+        .iter()
+        .map(|entry| (entry.path.clone(), entry.status))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            (PathBuf::from("existing.txt"), FileStatus::Modified),
+            (PathBuf::from("src/main.rs"), FileStatus::Created),
+            (PathBuf::from("src"), FileStatus::Created),
+        ]
+    );
+    assert_eq!(manifest.files[1].hash, Some(content_hash(b"fn main() {}")));
+
+    let (_, latest) = Manifest::latest(&DiskFilesystem, &workdir)
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest, manifest);
+    fs::remove_dir_all(workdir).unwrap();
+}
+
+#[test]
+fn undo() {
+    let workdir = workdir("undo");
+    let manifest = generate(&workdir);
+
+    manifest.undo(&DiskFilesystem, &workdir, false).unwrap();
+    assert_eq!(
+        fs::read_to_string(workdir.join("existing.txt")).unwrap(),
+        "original"
+    );
+    assert!(!workdir.join("src").exists());
+    fs::remove_dir_all(workdir).unwrap();
+}
+
+#[test]
+fn changed_since() {
+    let workdir = workdir("changed");
+    let manifest = generate(&workdir);
+    fs::write(workdir.join("src/main.rs"), "edited").unwrap();
+
+    let err = manifest.undo(&DiskFilesystem, &workdir, false).unwrap_err();
+    assert!(err.message.contains("src/main.rs"));
+    // Nothing is undone without --force
+    assert_eq!(
+        fs::read_to_string(workdir.join("existing.txt")).unwrap(),
+        "generated"
+    );
+
+    manifest.undo(&DiskFilesystem, &workdir, true).unwrap();
+    assert!(!workdir.join("src").exists());
+    fs::remove_dir_all(workdir).unwrap();
+}
+
+#[test]
+fn removed_link() {
+    let workdir = Path::new("/work");
+    let fs = Rc::new(MemoryFilesystem::new());
+    fs.write(&workdir.join("shared/.editorconfig"), b"root = true")
+        .unwrap();
+    let link = workdir.join("app/.editorconfig");
+    fs.symlink(Path::new("../shared/.editorconfig"), &link)
+        .unwrap();
+    let config = brix_cli::Config {
+        workdir: workdir.to_path_buf(),
+        ..brix_cli::Config::default()
+    };
+
+    let journal = Journal::with_filesystem(true, fs.clone());
+    journal.record(&link).unwrap();
+    fs.remove_file(&link).unwrap();
+    let manifest = Manifest::record(&*fs, &config, HashMap::new(), &journal).unwrap();
+
+    manifest.undo(&*fs, workdir, false).unwrap();
+    assert_eq!(
+        fs.read_link(&link).unwrap(),
+        PathBuf::from("../shared/.editorconfig")
+    );
+}
@@ -0,0 +1,267 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [Journal], which records the state of every path before a command changes it
+//! so that a failed run can be rolled back.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use brix_errors::BrixError;
+
+use crate::filesystem::{DiskFilesystem, Filesystem};
+
+#[cfg(test)]
+mod tests {
+    mod rollback;
+}
+
+/// The state of a path before it was first touched.
+#[derive(Debug)]
+enum PriorState {
+    /// The path didn't exist and is removed on rollback, along with anything inside of it.
+    Absent,
+    /// The file existed with the given contents, which are written back on rollback.
+    File(Vec<u8>),
+    /// The path was a link to the target, which is linked again on rollback.
+    Symlink(PathBuf),
+    /// The directory existed and is created again on rollback in case it was removed.
+    Dir,
+}
+
+/// A path that was changed during the run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The file or directory didn't exist before the run.
+    Created(PathBuf),
+    /// The file existed with the given contents before the run.
+    Modified { path: PathBuf, original: Vec<u8> },
+    /// The path was a link to the target before the run.
+    Linked { path: PathBuf, target: PathBuf },
+    /// The path was changed by a plugin, which only reports it afterwards
+    /// so its state before the run isn't known.
+    Touched(PathBuf),
+}
+
+/// Records the prior state of paths before commands change them.
+#[derive(Debug)]
+pub struct Journal {
+    enabled: bool,
+    fs: Rc<dyn Filesystem>,
+    entries: RefCell<Vec<(PathBuf, PriorState)>>,
+    changes: RefCell<Vec<Change>>,
+    /// What commands rendered for a file before it was written,
+    /// which `brix update` merges against in the next run.
+    renders: RefCell<HashMap<PathBuf, Vec<u8>>>,
+}
+
+impl Journal {
+    /// Creates an empty journal, which can only be rolled back if it's `enabled`.
+    pub fn new(enabled: bool) -> Self {
+        Self::with_filesystem(enabled, Rc::new(DiskFilesystem))
+    }
+
+    /// Creates an empty journal for the paths of the filesystem.
+    pub fn with_filesystem(enabled: bool, fs: Rc<dyn Filesystem>) -> Self {
+        Self {
+            enabled,
+            fs,
+            entries: RefCell::new(Vec::new()),
+            changes: RefCell::new(Vec::new()),
+            renders: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Records the state of the path before it's written to.
+    /// If the path doesn't exist, the topmost missing directory is recorded instead
+    /// since any parent directories are created along with it.
+    /// Only the first record of a path is kept.
+    pub fn record(&self, path: &Path) -> Result<(), BrixError> {
+        let mut target = path.to_path_buf();
+        // Links are kept as they are, whether or not what they point to exists
+        let state = if self.fs.is_symlink(path) {
+            PriorState::Symlink(self.fs.read_link(path)?)
+        } else if self.fs.exists(path) {
+            if self.fs.is_dir(path) {
+                // Existing directories are never removed or changed
+                return Ok(());
+            }
+            PriorState::File(self.fs.read(path)?)
+        } else {
+            while let Some(parent) = target.parent() {
+                if parent.as_os_str().is_empty() || self.fs.exists(parent) {
+                    break;
+                }
+                target = parent.to_path_buf();
+            }
+            PriorState::Absent
+        };
+
+        let change = match &state {
+            PriorState::Absent => Change::Created(path.to_path_buf()),
+            PriorState::File(contents) => Change::Modified {
+                path: path.to_path_buf(),
+                original: contents.clone(),
+            },
+            PriorState::Symlink(link) => Change::Linked {
+                path: path.to_path_buf(),
+                target: link.clone(),
+            },
+            PriorState::Dir => unreachable!("directories are recorded with record_dir"),
+        };
+        let mut changes = self.changes.borrow_mut();
+        if !changes.iter().any(|existing| existing.path() == path) {
+            changes.push(change);
+        }
+
+        let mut entries = self.entries.borrow_mut();
+        // A directory that was removed can still have new files written into it
+        let recorded = entries.iter().any(|(existing, existing_state)| {
+            (existing == &target && !matches!(existing_state, PriorState::Dir))
+                || (matches!(existing_state, PriorState::Absent) && target.starts_with(existing))
+        });
+        if !recorded {
+            entries.push((target, state));
+        }
+        Ok(())
+    }
+
+    /// Records the directory and everything inside of it before it's removed,
+    /// including empty directories which [record](Journal::record) leaves out.
+    pub fn record_dir(&self, path: &Path) -> Result<(), BrixError> {
+        if !self.fs.is_dir(path) || self.fs.is_symlink(path) {
+            return self.record(path);
+        }
+        {
+            let mut entries = self.entries.borrow_mut();
+            let recorded = entries.iter().any(|(existing, existing_state)| {
+                existing == path
+                    || (matches!(existing_state, PriorState::Absent) && path.starts_with(existing))
+            });
+            if !recorded {
+                entries.push((path.to_path_buf(), PriorState::Dir));
+            }
+        }
+        for child in self.fs.read_dir(path)? {
+            self.record_dir(&child)?;
+        }
+        Ok(())
+    }
+
+    /// Restores every recorded path in the reverse order they were recorded
+    /// and returns the paths that were restored. A path that can't be restored doesn't stop
+    /// the others from being restored, and every failure is returned together afterwards.
+    pub fn rollback(&self) -> Result<Vec<PathBuf>, BrixError> {
+        if !self.enabled {
+            return Ok(vec![]);
+        }
+
+        let mut restored = Vec::new();
+        let mut failures = Vec::new();
+        let entries = self.entries.replace(Vec::new());
+        for (path, state) in entries.into_iter().rev() {
+            match self.restore(&path, state) {
+                Ok(true) => restored.push(path),
+                Ok(false) => {}
+                Err(err) => failures.push(format!("'{}': {}", path.display(), err)),
+            }
+        }
+
+        match failures.is_empty() {
+            true => Ok(restored),
+            false => Err(BrixError::with(&format!(
+                "unable to restore {} path(s), restored {} other path(s):\n  {}",
+                failures.len(),
+                restored.len(),
+                failures.join("\n  ")
+            ))),
+        }
+    }
+
+    /// Puts the path back the way it was, returning whether there was anything to restore.
+    fn restore(&self, path: &Path, state: PriorState) -> Result<bool, BrixError> {
+        match state {
+            PriorState::Absent if self.fs.is_symlink(path) => self.fs.remove_file(path)?,
+            PriorState::Absent if self.fs.is_dir(path) => self.fs.remove_dir_all(path)?,
+            PriorState::Absent if self.fs.exists(path) => self.fs.remove_file(path)?,
+            PriorState::Absent => return Ok(false),
+            PriorState::File(contents) => {
+                // The directory might have been removed along with the file
+                if let Some(parent) = path.parent() {
+                    self.fs.create_dir_all(parent)?;
+                }
+                self.fs.write(path, &contents)?
+            }
+            PriorState::Dir if self.fs.is_dir(path) => return Ok(false),
+            PriorState::Dir => self.fs.create_dir_all(path)?,
+            PriorState::Symlink(target) => {
+                if self.fs.is_symlink(path) || self.fs.is_file(path) {
+                    self.fs.remove_file(path)?;
+                } else if self.fs.is_dir(path) {
+                    self.fs.remove_dir_all(path)?;
+                }
+                if let Some(parent) = path.parent() {
+                    self.fs.create_dir_all(parent)?;
+                }
+                self.fs.symlink(&target, path)?
+            }
+        }
+        Ok(true)
+    }
+
+    /// Records a path a plugin reported changing. Since it was already changed,
+    /// it can't be rolled back and is only kept for the manifest.
+    pub fn record_touched(&self, path: &Path) {
+        let mut changes = self.changes.borrow_mut();
+        if !changes.iter().any(|existing| existing.path() == path) {
+            changes.push(Change::Touched(path.to_path_buf()));
+        }
+    }
+
+    /// Records what was rendered for the file, which may differ from what was
+    /// written to it when the render was merged with its current contents.
+    pub fn record_render(&self, path: &Path, contents: Vec<u8>) {
+        self.renders
+            .borrow_mut()
+            .insert(path.to_path_buf(), contents);
+    }
+
+    /// What was rendered for the file during the run, if anything.
+    pub fn render(&self, path: &Path) -> Option<Vec<u8>> {
+        self.renders.borrow().get(path).cloned()
+    }
+
+    /// Every path that was changed in the order they were first recorded,
+    /// including any parent directories that were created along the way.
+    pub fn changes(&self) -> Vec<Change> {
+        let mut changes = self.changes.borrow().clone();
+        for (path, state) in self.entries.borrow().iter() {
+            if matches!(state, PriorState::Absent) && !changes.iter().any(|c| c.path() == path) {
+                changes.push(Change::Created(path.clone()));
+            }
+        }
+        changes
+    }
+}
+
+impl Change {
+    /// The path that was changed.
+    pub fn path(&self) -> &Path {
+        match self {
+            Change::Created(path) => path,
+            Change::Modified { path, .. } => path,
+            Change::Linked { path, .. } => path,
+            Change::Touched(path) => path,
+        }
+    }
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
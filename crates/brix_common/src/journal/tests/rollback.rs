@@ -0,0 +1,157 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::filesystem::{Filesystem, MemoryFilesystem};
+use crate::journal::Journal;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("brix-journal-{}-{}", name, std::process::id()));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn restores() {
+    let dir = temp_dir("restores");
+    let existing = dir.join("existing.txt");
+    fs::write(&existing, "original").unwrap();
+
+    let journal = Journal::new(true);
+    journal.record(&existing).unwrap();
+    fs::write(&existing, "changed").unwrap();
+
+    // Only the topmost created directory is recorded
+    let created = dir.join("a/b/created.txt");
+    journal.record(&created).unwrap();
+    journal.record(&dir.join("a/other.txt")).unwrap();
+    fs::create_dir_all(created.parent().unwrap()).unwrap();
+    fs::write(&created, "new").unwrap();
+
+    let restored = journal.rollback().unwrap();
+    assert_eq!(restored, vec![dir.join("a"), existing.clone()]);
+    assert_eq!(fs::read_to_string(&existing).unwrap(), "original");
+    assert!(!dir.join("a").exists());
+
+    // Nothing is left to roll back
+    assert!(journal.rollback().unwrap().is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn first_record_wins() {
+    let dir = temp_dir("first");
+    let file = dir.join("file.txt");
+    fs::write(&file, "first").unwrap();
+
+    let journal = Journal::new(true);
+    journal.record(&file).unwrap();
+    fs::write(&file, "second").unwrap();
+    journal.record(&file).unwrap();
+    fs::write(&file, "third").unwrap();
+
+    journal.rollback().unwrap();
+    assert_eq!(fs::read_to_string(&file).unwrap(), "first");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn disabled() {
+    let dir = temp_dir("disabled");
+    let file = dir.join("file.txt");
+
+    let journal = Journal::new(false);
+    journal.record(&file).unwrap();
+    fs::write(&file, "kept").unwrap();
+
+    assert!(journal.rollback().unwrap().is_empty());
+    assert!(file.exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn restores_links() {
+    let fs = Rc::new(MemoryFilesystem::new());
+    fs.write(Path::new("work/shared/config.toml"), b"shared")
+        .unwrap();
+    let links = [
+        ("work/app/config.toml", "../shared/config.toml"),
+        ("work/app/shared", "../shared"),
+        ("work/app/dangling", "missing"),
+    ];
+    for (link, target) in links.iter() {
+        fs.symlink(Path::new(target), Path::new(link)).unwrap();
+    }
+
+    let journal = Journal::with_filesystem(true, fs.clone());
+    for link in [links[0].0, links[2].0].iter() {
+        journal.record(Path::new(link)).unwrap();
+        fs.remove_file(Path::new(link)).unwrap();
+    }
+    let moved = Path::new("work/app/moved");
+    journal.record(Path::new(links[1].0)).unwrap();
+    journal.record(moved).unwrap();
+    fs.rename(Path::new(links[1].0), moved).unwrap();
+
+    journal.rollback().unwrap();
+    for (link, target) in links.iter() {
+        assert_eq!(fs.read_link(Path::new(link)).unwrap(), Path::new(target));
+    }
+    assert!(!fs.is_symlink(moved));
+    assert_eq!(
+        fs.read(Path::new("work/app/config.toml")).unwrap(),
+        b"shared"
+    );
+}
+
+#[test]
+fn keeps_going() {
+    let fs = Rc::new(MemoryFilesystem::new());
+    let blocked = Path::new("work/blocked/file.txt");
+    let other = Path::new("work/other.txt");
+    fs.write(blocked, b"blocked").unwrap();
+    fs.write(other, b"original").unwrap();
+
+    let journal = Journal::with_filesystem(true, fs.clone());
+    journal.record(other).unwrap();
+    journal.record(blocked).unwrap();
+    fs.write(other, b"changed").unwrap();
+    // The file can't be written back once its directory is a file
+    fs.remove_dir_all(Path::new("work/blocked")).unwrap();
+    fs.write(Path::new("work/blocked"), b"").unwrap();
+
+    let err = journal.rollback().unwrap_err();
+    assert!(err
+        .message
+        .contains("unable to restore 1 path(s), restored 1 other path(s)"));
+    assert!(err.message.contains("'work/blocked/file.txt'"));
+    assert_eq!(fs.read(other).unwrap(), b"original");
+}
+
+#[test]
+fn removed_directory() {
+    let fs = Rc::new(MemoryFilesystem::new());
+    fs.write(Path::new("work/app/main.rs"), b"main").unwrap();
+    fs.create_dir_all(Path::new("work/app/empty")).unwrap();
+
+    let journal = Journal::with_filesystem(true, fs.clone());
+    journal.record_dir(Path::new("work/app")).unwrap();
+    fs.remove_dir_all(Path::new("work/app")).unwrap();
+    // Written after the directory was removed, so it's left out after the rollback
+    let created = Path::new("work/app/created.rs");
+    journal.record(created).unwrap();
+    fs.write(created, b"created").unwrap();
+
+    journal.rollback().unwrap();
+    assert!(fs.is_dir(Path::new("work/app/empty")));
+    assert_eq!(fs.read(Path::new("work/app/main.rs")).unwrap(), b"main");
+    assert!(!fs.exists(created));
+}
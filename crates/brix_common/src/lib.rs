@@ -6,7 +6,13 @@
 //! # Brix Common
 //! Common crate currently used for storing [AppContext] and other common functions in the future.
 
+pub mod answers;
 mod app_context;
 pub mod context;
+pub mod filesystem;
+pub mod history;
+pub mod journal;
 
 pub use app_context::AppContext;
+pub use filesystem::{DiskFilesystem, Filesystem, MemoryFilesystem};
+pub use journal::Journal;
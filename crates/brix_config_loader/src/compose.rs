@@ -0,0 +1,175 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Module responsible for composing a [RawConfig] with the configs it `extends` and `include`s.
+//!
+//! Configs are merged with the following precedence, from lowest to highest:
+//! 1) The `extends` base config.
+//! 2) Each `include` in the order they are listed.
+//! 3) The config itself.
+//!
+//! Commands run in that same order, and context is merged key by key with the later config
+//! taking priority. Arguments are only inherited from the base config.
+
+use log::debug;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use brix_common::context::merge_into;
+use brix_errors::BrixError;
+
+use crate::{search_for_module_declarations, ConfigLoader, ConfigParser, RawConfig};
+
+#[cfg(test)]
+mod tests {
+    mod compose;
+}
+
+impl<'a> ConfigLoader<'a> {
+    /// Parses a config file with the parser matching its extension.
+    pub(crate) fn parse_file(&self, path: &PathBuf) -> Result<RawConfig, BrixError> {
+        let parser = self.parser_for(path).ok_or_else(|| {
+            BrixError::with(&format!(
+                "the file extension of '{}' is not supported for configs",
+                path.display()
+            ))
+        })?;
+        let contents = fs::read_to_string(path)?;
+        parser.parse(&contents)
+    }
+
+    /// Recursively merges the configs that `config` extends and includes into it.
+    /// `chain` contains every config currently being composed and is used to detect cycles.
+    pub(crate) fn compose(
+        &self,
+        mut config: RawConfig,
+        file: &Path,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<RawConfig, BrixError> {
+        let file = file.canonicalize()?;
+        let dir = file.parent().unwrap().to_path_buf();
+        chain.push(file);
+
+        let mut composed = RawConfig::default();
+        let base = config.extends.take().map(|base| (base, true));
+        let includes = config.include.take().unwrap_or_default();
+
+        for (reference, is_base) in base
+            .into_iter()
+            .chain(includes.into_iter().map(|include| (include, false)))
+        {
+            let path = self.resolve_reference(&reference, &dir)?;
+            if chain.contains(&path) {
+                let cycle: Vec<String> = chain
+                    .iter()
+                    .chain(std::iter::once(&path))
+                    .map(|p| p.display().to_string())
+                    .collect();
+                return Err(BrixError::with(&format!(
+                    "config inclusion cycle detected: {}",
+                    cycle.join(" -> ")
+                )));
+            }
+
+            debug!("composing '{}' from {}", reference, path.display());
+            let mut parent = self.parse_file(&path)?;
+            parent.relative_to(path.parent().unwrap());
+            let mut parent = self.compose(parent, &path, chain)?;
+            if !is_base {
+                parent.args = None;
+            }
+            composed.merge(parent);
+        }
+
+        chain.pop();
+        composed.merge(config);
+        Ok(composed)
+    }
+
+    /// Finds the config a reference such as `base` or `common/license` points to.
+    /// Searches the directory of the current config first, then each of its ancestors.
+    fn resolve_reference(&self, reference: &str, dir: &Path) -> Result<PathBuf, BrixError> {
+        let reference_path = Path::new(reference);
+        let name = reference_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let path = reference_path
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        for current in dir.ancestors() {
+            let declarations = search_for_module_declarations(current, &path, &name)?;
+            let found = declarations.into_iter().find(|declaration| {
+                declaration.extension().is_some() && self.parser_for(declaration).is_some()
+            });
+            if let Some(found) = found {
+                return Ok(found.canonicalize()?);
+            }
+
+            if Some(current) == self.cli_config.home_dir.as_deref() {
+                break;
+            }
+        }
+
+        Err(BrixError::with(&format!(
+            "could not find config '{}' from {}",
+            reference,
+            dir.display()
+        )))
+    }
+
+    /// Returns the first parser supporting the extension of the path.
+    fn parser_for(&self, path: &PathBuf) -> Option<&dyn ConfigParser> {
+        self.parsers
+            .iter()
+            .find(|parser| parser.matches(path))
+            .map(Box::as_ref)
+    }
+}
+
+impl RawConfig {
+    /// Merges another config into this one, with `other` taking priority.
+    fn merge(&mut self, other: RawConfig) {
+        if other.args.is_some() {
+            self.args = other.args;
+        }
+
+        // Redeclaring an input replaces the inherited one
+        let mut inputs = self.inputs.take().unwrap_or_default();
+        for input in other.inputs.into_iter().flatten() {
+            inputs.retain(|existing| existing.name != input.name);
+            inputs.push(input);
+        }
+        if !inputs.is_empty() {
+            self.inputs = Some(inputs);
+        }
+
+        if let Some(other_context) = other.context {
+            let context = self.context.get_or_insert_with(Default::default);
+            for (key, value) in other_context.into_iter() {
+                merge_into(context, key, value);
+            }
+        }
+
+        self.commands.extend(other.commands);
+    }
+
+    /// Makes the sources of every command absolute using the directory of the config they come from,
+    /// since sources are otherwise resolved relative to the config that was run.
+    fn relative_to(&mut self, dir: &Path) {
+        for command in self.commands.iter_mut() {
+            for raw in command.values_mut() {
+                if let Some(Value::String(source)) = raw.params.get("source") {
+                    let source = dir.join(source).to_string_lossy().to_string();
+                    raw.params
+                        .insert(String::from("source"), Value::String(source));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+use std::path::PathBuf;
+
+use crate::parsers::ConfigParser;
+use crate::{ConfigLoader, ParserList, YamlConfigParser};
+
+fn compose(file: &str) -> Result<crate::RawConfig, brix_errors::BrixError> {
+    let cli_config = brix_cli::Config::default();
+    let parsers: ParserList = vec![Box::new(YamlConfigParser {})];
+    let loader = ConfigLoader::new(parsers, &cli_config);
+
+    let path = PathBuf::from("src/compose").join(file);
+    let config = loader.parse_file(&path)?;
+    loader.compose(config, &path, &mut vec![])
+}
+
+#[test]
+fn extends_and_include() {
+    let config = compose("app/app.brix.yml").unwrap();
+
+    let args = config.args.unwrap();
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0].name, "project");
+
+    let context = config.context.unwrap();
+    assert_eq!(context["license"], json!("Apache-2.0"));
+    assert_eq!(
+        context["author"],
+        json!({"name": "App", "email": "brix@example.com"})
+    );
+
+    let commands: Vec<&String> = config
+        .commands
+        .iter()
+        .map(|command| command.keys().next().unwrap())
+        .collect();
+    assert_eq!(commands, vec!["mkdir", "copy", "template"]);
+}
+
+#[test]
+fn include_sources_are_relative_to_their_config() {
+    let config = compose("app/app.brix.yml").unwrap();
+    let common = PathBuf::from("src/compose/common").canonicalize().unwrap();
+
+    let copy = config.commands[1].get("copy").unwrap();
+    assert_eq!(
+        copy.params["source"],
+        json!(common.join("LICENSE").to_string_lossy())
+    );
+    let template = config.commands[2].get("template").unwrap();
+    assert_eq!(template.params["source"], json!("main.hbs"));
+}
+
+#[test]
+fn cycle() {
+    let err = compose("cycle/first.brix.yml").unwrap_err();
+    let dir = PathBuf::from("src/compose/cycle").canonicalize().unwrap();
+    let first = dir.join("first.brix.yml");
+    let second = dir.join("second.brix.yml");
+    assert_eq!(
+        err.message,
+        format!(
+            "config inclusion cycle detected: {} -> {} -> {}",
+            first.display(),
+            second.display(),
+            first.display()
+        )
+    );
+}
+
+#[test]
+fn missing() {
+    let cli_config = brix_cli::Config::default();
+    let loader = ConfigLoader::new(vec![Box::new(YamlConfigParser {})], &cli_config);
+    let config = YamlConfigParser {}.parse("include: [nope]").unwrap();
+    assert!(loader
+        .compose(
+            config,
+            &PathBuf::from("src/compose/base.brix.yml"),
+            &mut vec![]
+        )
+        .unwrap_err()
+        .message
+        .starts_with("could not find config 'nope'"));
+}
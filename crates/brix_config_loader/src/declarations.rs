@@ -0,0 +1,104 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Module responsible for finding the module declaration files of a language and config name.
+
+use log::{debug, info};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use brix_errors::BrixError;
+
+/// The directory containing the configs for the language, relative to the config directory.
+pub fn module_dir(config: &brix_cli::Config) -> PathBuf {
+    let default_config = PathBuf::from(".config/brix");
+    let config_root = Path::new(config.config_dir.as_ref().unwrap_or(&default_config));
+    let language_dir = Path::new(&config.language);
+    config_root.join(language_dir)
+}
+
+/// The main wrapper function for finding a module declaration file.
+/// Uses the `config_dir` to determine whether to search in parent directories or not.
+pub fn modules_from_config(
+    dir: &PathBuf,
+    config: &brix_cli::Config,
+) -> Result<Vec<PathBuf>, BrixError> {
+    let declarations;
+    if config.config_dir.is_none() {
+        declarations = search_for_module_declarations_all(dir.to_str().unwrap(), &config)?;
+    } else {
+        declarations = search_for_module_declarations(
+            config.config_dir.as_ref().unwrap(),
+            dir.to_str().unwrap(),
+            &config.config_name,
+        )?;
+    }
+
+    if declarations.len() == 0 {
+        return Err(BrixError::with(&format!(
+            "Could not find module declaration for '{}' in {}",
+            config.config_name,
+            dir.display()
+        )));
+    }
+
+    Ok(declarations)
+}
+
+/// Uses `search_for_module_declarations` up to the home directory to find a module declaration.
+fn search_for_module_declarations_all(
+    path: &str,
+    config: &brix_cli::Config,
+) -> Result<Vec<PathBuf>, BrixError> {
+    let mut current_path = config.workdir.clone();
+
+    loop {
+        debug!("Looking for config directory in {:?}", current_path);
+        let declarations =
+            search_for_module_declarations(&current_path, &path, &config.config_name)?;
+        if declarations.len() > 0 {
+            return Ok(declarations);
+        }
+
+        if &current_path == config.home_dir.as_ref().unwrap() {
+            return Err(BrixError::with(&format!(
+                "Could not find module declaration for '{}' in {}",
+                config.config_name, path
+            )));
+        }
+
+        current_path = current_path.parent().unwrap().to_path_buf();
+    }
+}
+
+/// Finds valid module declarations in the given directory.
+pub fn search_for_module_declarations(
+    current_path: &Path,
+    path: &str,
+    name: &str,
+) -> Result<Vec<PathBuf>, BrixError> {
+    let mut results = Vec::new();
+
+    let search_path = current_path.join(path);
+    if !search_path.exists() {
+        // Should not error if the directory doesn't exist,
+        // just return an empty vec of results
+        return Ok(vec![]);
+    }
+
+    let paths = fs::read_dir(search_path)?;
+    for path in paths {
+        let path = path.unwrap().path();
+        if path.is_file() {
+            let stem = path.file_stem().unwrap();
+            if name == stem || format!("{}.brix", name) == stem.to_str().unwrap() {
+                results.push(path);
+            }
+        }
+    }
+    info!("RESULTS: {:?}", results);
+
+    Ok(results)
+}
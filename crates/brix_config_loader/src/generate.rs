@@ -0,0 +1,132 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Module responsible for running another config from the `generate` command.
+
+use log::info;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use brix_commands::{CommandRegistry, Generator, PlannedAction};
+use brix_common::AppContext;
+use brix_errors::BrixError;
+use brix_processor::ProcessorCore;
+
+use crate::{default_parsers, module_dir, modules_from_config, CommandList, ConfigLoader};
+
+/// The [Generator] used by the `generate` command, which loads the config
+/// the same way as the CLI and runs its commands with a child [AppContext].
+pub struct ConfigGenerator {
+    registry: CommandRegistry,
+}
+
+impl ConfigGenerator {
+    /// Creates the generator, which runs configs with the commands in the registry.
+    pub fn new(registry: CommandRegistry) -> Self {
+        Self { registry }
+    }
+
+    /// Loads the config with a child [AppContext] and hands its commands over to `f`.
+    fn with_child<T>(
+        &self,
+        language: &str,
+        config: &str,
+        context: HashMap<String, Value>,
+        app_context: &AppContext,
+        f: impl FnOnce(CommandList, &AppContext, &Path) -> Result<T, BrixError>,
+    ) -> Result<T, BrixError> {
+        let chain = app_context.chain();
+        let next = (language.to_string(), config.to_string());
+        if chain.contains(&next) {
+            let cycle: Vec<String> = chain
+                .iter()
+                .chain(std::iter::once(&next))
+                .map(|(language, config)| format!("{}/{}", language, config))
+                .collect();
+            return Err(BrixError::with(&format!(
+                "config generate cycle detected: {}",
+                cycle.join(" -> ")
+            )));
+        }
+
+        // The context is passed like `--set` so that it fills in the
+        // arguments and inputs of the child config instead of asking for them
+        let child_config =
+            app_context
+                .config
+                .for_config(language, config, context.into_iter().collect());
+
+        let declarations = modules_from_config(&module_dir(&child_config), &child_config)?;
+        let mut loader = ConfigLoader::new(default_parsers(), &child_config)
+            .with_registry(self.registry.clone());
+        let config_file = loader.load(declarations)?;
+        info!("generating from '{}'", config_file.display());
+
+        let child_context = app_context.child(ProcessorCore::new(), &child_config);
+        let commands = loader.run(&child_context).map_err(|err| {
+            BrixError::with(&format!(
+                "Error loading config at '{}':\n{}",
+                config_file.display(),
+                err
+            ))
+        })?;
+
+        f(commands, &child_context, &config_file)
+    }
+}
+
+impl Generator for ConfigGenerator {
+    fn generate(
+        &self,
+        language: &str,
+        config: &str,
+        context: HashMap<String, Value>,
+        app_context: &AppContext,
+    ) -> Result<(), BrixError> {
+        self.with_child(
+            language,
+            config,
+            context,
+            app_context,
+            |commands, child_context, config_file| {
+                for (command, args) in commands.into_iter() {
+                    info!("running {} command from '{}'", command.name(), config);
+                    command.run(args, child_context).map_err(|err| {
+                        BrixError::with(&format!(
+                            "Error running {} command in '{}':\n{}",
+                            command.name(),
+                            config_file.display(),
+                            err
+                        ))
+                    })?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn plan(
+        &self,
+        language: &str,
+        config: &str,
+        context: HashMap<String, Value>,
+        app_context: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        self.with_child(
+            language,
+            config,
+            context,
+            app_context,
+            |commands, child_context, _| {
+                let mut actions = Vec::new();
+                for (command, args) in commands.into_iter() {
+                    actions.extend(command.plan(args, child_context)?);
+                }
+                Ok(actions)
+            },
+        )
+    }
+}
@@ -0,0 +1,322 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Module responsible for prompting the user for every [RawInput] declared in a config.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use brix_cli::input::{do_confirm, do_input};
+use brix_cli::select::{do_multi_select, do_select_prompt};
+use brix_common::context::{cli_config_to_map, cli_overrides_to_map};
+use brix_common::AppContext;
+use brix_errors::{BrixError, BrixErrorKind};
+
+use crate::{ConfigLoader, RawConfig};
+
+#[cfg(test)]
+mod tests {
+    mod defaults;
+    mod overrides;
+    mod parse;
+}
+
+/// The type of a declared input, which decides the prompt used to ask for it.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputType {
+    #[default]
+    String,
+    Bool,
+    Int,
+    Select,
+    Multiselect,
+}
+
+/// A single input declared in the `inputs` section of a config.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RawInput {
+    pub(crate) name: String,
+    #[serde(rename = "type", default)]
+    kind: InputType,
+    prompt: Option<String>,
+    default: Option<Value>,
+    validate: Option<String>,
+    options: Option<Vec<String>>,
+}
+
+impl<'a> ConfigLoader<'a> {
+    /// Asks for each declared input in order and returns the answers.
+    /// Defaults are templated with the CLI context and all of the previous answers.
+    pub fn ask_inputs(
+        &self,
+        config: &RawConfig,
+        app_context: &AppContext,
+    ) -> Result<HashMap<String, Value>, BrixError> {
+        let mut answers = HashMap::new();
+        let cli_context = cli_config_to_map(self.cli_config, &config.positionals())?;
+        let overrides = self.cli_overrides(config)?;
+
+        for input in config.inputs.iter().flatten() {
+            // Inputs set with `--set` on the command line aren't asked for
+            if self.is_overridden(input) {
+                answers.insert(input.name.clone(), overrides[&input.name].clone());
+                continue;
+            }
+
+            let mut context = cli_context.clone();
+            context.extend(answers.clone());
+
+            let default = match &input.default {
+                Some(default) => Some(
+                    app_context
+                        .processor
+                        .process_value(default.clone(), &brix_processor::create_context(context))?,
+                ),
+                None => None,
+            };
+
+            let answer = input
+                .ask(default, self.cli_config.is_interactive())
+                .map_err(|err| BrixError {
+                    kind: err.kind,
+                    message: format!("unable to ask for input '{}': {}", input.name, err.message),
+                })?;
+            answers.insert(input.name.clone(), answer);
+        }
+
+        Ok(answers)
+    }
+
+    /// The context from `--set` and `--set-json`, where the inputs that are set
+    /// are converted to their type and checked the same way as an answer.
+    pub(crate) fn cli_overrides(
+        &self,
+        config: &RawConfig,
+    ) -> Result<HashMap<String, Value>, BrixError> {
+        let mut overrides = cli_overrides_to_map(self.cli_config);
+        for input in config.inputs.iter().flatten() {
+            if self.is_overridden(input) {
+                let value = input.accept(&overrides[&input.name])?;
+                overrides.insert(input.name.clone(), value);
+            }
+        }
+        Ok(overrides)
+    }
+
+    fn is_overridden(&self, input: &RawInput) -> bool {
+        self.cli_config
+            .overrides
+            .iter()
+            .any(|(key, _)| key == &input.name)
+    }
+}
+
+impl RawInput {
+    /// Converts a value given on the command line into the type of the input,
+    /// failing if it isn't one of the options or doesn't match `validate`.
+    /// Multiselects take a list or the options separated by commas.
+    fn accept(&self, value: &Value) -> Result<Value, BrixError> {
+        let validator = self.validator()?;
+        let invalid = |err: BrixError| self.invalid(&err.message);
+        let accepted = match self.kind {
+            InputType::String => {
+                let value = value_to_string(value);
+                validator(&value).map_err(|err| self.invalid(&err))?;
+                Value::from(value)
+            }
+            InputType::Int => {
+                let value = value_to_int(value).map_err(invalid)?;
+                validator(&value.to_string()).map_err(|err| self.invalid(&err))?;
+                Value::from(value)
+            }
+            InputType::Bool => Value::from(value_to_bool(value).map_err(invalid)?),
+            InputType::Select => Value::from(self.option(&value_to_string(value))?),
+            InputType::Multiselect => {
+                let values: Vec<String> = match value {
+                    Value::Array(values) => values.iter().map(value_to_string).collect(),
+                    other => value_to_string(other)
+                        .split(',')
+                        .map(|value| value.trim().to_string())
+                        .filter(|value| !value.is_empty())
+                        .collect(),
+                };
+                let options = values
+                    .iter()
+                    .map(|value| self.option(value))
+                    .collect::<Result<Vec<&str>, BrixError>>()?;
+                Value::from(options)
+            }
+        };
+        Ok(accepted)
+    }
+
+    /// The option matching the value, for select and multiselect inputs.
+    fn option(&self, value: &str) -> Result<&str, BrixError> {
+        let options = self.options()?;
+        options
+            .iter()
+            .find(|option| **option == value)
+            .copied()
+            .ok_or_else(|| {
+                self.invalid(&format!(
+                    "'{}' is not one of the options {}",
+                    value,
+                    options.join(", ")
+                ))
+            })
+    }
+
+    /// Checks answers against the `validate` regex, if there is one.
+    fn validator(&self) -> Result<impl Fn(&String) -> Result<(), String>, BrixError> {
+        let regex = match &self.validate {
+            Some(validate) => Some(Regex::new(validate).map_err(|err| {
+                BrixError::with(&format!("invalid validation regex '{}': {}", validate, err))
+            })?),
+            None => None,
+        };
+        Ok(move |value: &String| -> Result<(), String> {
+            match &regex {
+                Some(re) if !re.is_match(value) => Err(format!(
+                    "'{}' does not match the expression '{}'",
+                    value,
+                    re.as_str()
+                )),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    /// Prompts for the input depending on its type and converts the answer into a value.
+    /// Without prompts the default is used and still has to match `validate`, which is `false` for bools, the first option for selects
+    /// and nothing for multiselects. Strings and ints without a default can't be answered.
+    fn ask(&self, default: Option<Value>, interactive: bool) -> Result<Value, BrixError> {
+        let prompt = self.prompt.clone().unwrap_or_else(|| self.name.clone());
+        let validator = self.validator()?;
+
+        let answer = match self.kind {
+            InputType::String => {
+                let default = default.as_ref().map(value_to_string);
+                if !interactive {
+                    let default = default.ok_or_else(|| self.needs_answer())?;
+                    validator(&default).map_err(|err| self.invalid(&err))?;
+                    return Ok(Value::from(default));
+                }
+                Value::from(do_input(&prompt, default, validator)?)
+            }
+            InputType::Int => {
+                let default = match &default {
+                    Some(value) => Some(value_to_int(value)?),
+                    None => None,
+                };
+                if !interactive {
+                    let default = default.ok_or_else(|| self.needs_answer())?;
+                    validator(&default.to_string()).map_err(|err| self.invalid(&err))?;
+                    return Ok(Value::from(default));
+                }
+                Value::from(do_input(&prompt, default, move |value: &i64| {
+                    validator(&value.to_string())
+                })?)
+            }
+            InputType::Bool => {
+                let default = match &default {
+                    Some(value) => value_to_bool(value)?,
+                    None => false,
+                };
+                if !interactive {
+                    return Ok(Value::from(default));
+                }
+                Value::from(do_confirm(&prompt, default)?)
+            }
+            InputType::Select => {
+                let options = self.options()?;
+                let default = default
+                    .as_ref()
+                    .map(value_to_string)
+                    .and_then(|d| options.iter().position(|o| *o == d))
+                    .unwrap_or(0);
+                if !interactive {
+                    return Ok(Value::from(options[default]));
+                }
+                let index = do_select_prompt(&prompt, options.clone(), default)?;
+                Value::from(options[index])
+            }
+            InputType::Multiselect => {
+                let options = self.options()?;
+                let defaults: Vec<String> = match &default {
+                    Some(Value::Array(values)) => values.iter().map(value_to_string).collect(),
+                    Some(value) => vec![value_to_string(value)],
+                    None => vec![],
+                };
+                let checked: Vec<bool> = options
+                    .iter()
+                    .map(|o| defaults.iter().any(|d| d == o))
+                    .collect();
+                let indexes = match interactive {
+                    true => do_multi_select(&prompt, options.clone(), &checked)?,
+                    false => (0..options.len()).filter(|i| checked[*i]).collect(),
+                };
+                Value::from(
+                    indexes
+                        .into_iter()
+                        .map(|i| options[i])
+                        .collect::<Vec<&str>>(),
+                )
+            }
+        };
+
+        Ok(answer)
+    }
+
+    fn needs_answer(&self) -> BrixError {
+        BrixError::with(&format!(
+            "there is no default and prompts are disabled, set it with --set {}=<value>",
+            self.name
+        ))
+    }
+
+    fn invalid(&self, reason: &str) -> BrixError {
+        BrixError {
+            kind: Some(BrixErrorKind::Validation),
+            message: format!("invalid value for input '{}': {}", self.name, reason),
+        }
+    }
+
+    /// Returns the options for select inputs, which are required to be non empty.
+    fn options(&self) -> Result<Vec<&str>, BrixError> {
+        match &self.options {
+            Some(options) if !options.is_empty() => {
+                Ok(options.iter().map(|o| o.as_str()).collect())
+            }
+            _ => Err(BrixError::with(
+                "the 'options' field is required for select inputs",
+            )),
+        }
+    }
+}
+
+/// Converts any value into a string without quoting strings.
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn value_to_int(value: &Value) -> Result<i64, BrixError> {
+    value_to_string(value)
+        .trim()
+        .parse::<i64>()
+        .map_err(|_| BrixError::with(&format!("'{}' is not an integer", value_to_string(value))))
+}
+
+fn value_to_bool(value: &Value) -> Result<bool, BrixError> {
+    value_to_string(value)
+        .trim()
+        .parse::<bool>()
+        .map_err(|_| BrixError::with(&format!("'{}' is not a boolean", value_to_string(value))))
+}
@@ -0,0 +1,79 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+
+use brix_errors::BrixErrorKind;
+
+use crate::parsers::{ConfigParser, YamlConfigParser};
+
+#[test]
+fn without_prompts() {
+    let config = YamlConfigParser {}
+        .parse(
+            r#"
+inputs:
+  - name: package
+    default: com.example
+  - name: with_tests
+    type: bool
+  - name: build
+    type: select
+    options: [gradle, maven]
+  - name: features
+    type: multiselect
+    options: [web, db, auth]
+    default: [db, auth]
+  - name: port
+    type: int
+"#,
+        )
+        .unwrap();
+
+    let inputs = config.inputs.unwrap();
+    let answers: Vec<String> = inputs
+        .iter()
+        .map(|input| match input.ask(input.default.clone(), false) {
+            Ok(answer) => answer.to_string(),
+            Err(err) => err.to_string(),
+        })
+        .collect();
+    assert_eq!(
+        answers,
+        [
+            json!("com.example").to_string(),
+            json!(false).to_string(),
+            json!("gradle").to_string(),
+            json!(["db", "auth"]).to_string(),
+            String::from(
+                "there is no default and prompts are disabled, set it with --set port=<value>"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn validate_default() {
+    let config = YamlConfigParser {}
+        .parse(
+            r#"
+inputs:
+  - name: package
+    default: "Com.Example"
+    validate: "^[a-z.]+$"
+  - name: port
+    type: int
+    default: 80
+    validate: "^[0-9]{4}$"
+"#,
+        )
+        .unwrap();
+
+    for input in config.inputs.unwrap().iter() {
+        let err = input.ask(input.default.clone(), false).unwrap_err();
+        assert_eq!(err.kind, Some(BrixErrorKind::Validation));
+        assert!(err.message.contains("does not match the expression"));
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::parsers::{ConfigParser, YamlConfigParser};
+use crate::ConfigLoader;
+use brix_common::AppContext;
+use brix_errors::{BrixError, BrixErrorKind};
+use brix_processor::ProcessorCore;
+
+const INPUTS: &str = r#"
+args: []
+inputs:
+  - name: with_tests
+    type: bool
+  - name: port
+    type: int
+    validate: "^[0-9]{4}$"
+  - name: build
+    type: select
+    options: [gradle, maven]
+  - name: features
+    type: multiselect
+    options: [web, db]
+"#;
+
+/// Answers the inputs with the values as if they were passed with `--set`.
+fn answer(overrides: Vec<(&str, Value)>) -> Result<HashMap<String, Value>, BrixError> {
+    let cli_config = brix_cli::Config {
+        overrides: overrides
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+        ..brix_cli::Config::default()
+    };
+    let loader = ConfigLoader::new(vec![Box::new(YamlConfigParser {})], &cli_config);
+    let config = YamlConfigParser {}.parse(INPUTS).unwrap();
+    let app_context = AppContext::new(ProcessorCore::new(), &cli_config);
+    loader.ask_inputs(&config, &app_context)
+}
+
+#[test]
+fn converted() {
+    let answers = answer(vec![
+        ("with_tests", json!("false")),
+        ("port", json!("8080")),
+        ("build", json!("maven")),
+        ("features", json!("web,db")),
+    ])
+    .unwrap();
+    assert_eq!(answers["with_tests"], json!(false));
+    assert_eq!(answers["port"], json!(8080));
+    assert_eq!(answers["build"], json!("maven"));
+    assert_eq!(answers["features"], json!(["web", "db"]));
+}
+
+#[test]
+fn invalid() {
+    let valid = vec![
+        ("with_tests", json!(true)),
+        ("port", json!(8080)),
+        ("build", json!("gradle")),
+        ("features", json!([])),
+    ];
+    let cases = vec![
+        ("with_tests", json!("maybe"), "'maybe' is not a boolean"),
+        ("port", json!("abc"), "'abc' is not an integer"),
+        ("port", json!("80"), "'80' does not match the expression"),
+        (
+            "build",
+            json!("ant"),
+            "'ant' is not one of the options gradle, maven",
+        ),
+        (
+            "features",
+            json!(["web", "auth"]),
+            "'auth' is not one of the options",
+        ),
+    ];
+    for (name, value, message) in cases {
+        let mut overrides = valid.clone();
+        overrides.push((name, value));
+        let err = answer(overrides).unwrap_err();
+        assert_eq!(err.kind, Some(BrixErrorKind::Validation));
+        assert!(err.message.contains(name), "{}", err.message);
+        assert!(err.message.contains(message), "{}", err.message);
+    }
+}
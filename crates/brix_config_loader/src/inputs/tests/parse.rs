@@ -0,0 +1,61 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+
+use crate::inputs::{value_to_bool, value_to_int, InputType};
+use crate::parsers::{ConfigParser, YamlConfigParser};
+
+#[test]
+fn inputs() {
+    let config = YamlConfigParser {}
+        .parse(
+            r#"
+inputs:
+  - name: package
+    prompt: Java package
+    default: "com.example.{{project}}"
+    validate: "^[a-z.]+$"
+  - name: with_tests
+    type: bool
+    default: true
+  - name: build
+    type: select
+    options: [gradle, maven]
+  - name: port
+    type: int
+commands:
+  - mkdir:
+      destination: src
+"#,
+        )
+        .unwrap();
+
+    let inputs = config.inputs.unwrap();
+    let kinds: Vec<InputType> = inputs.iter().map(|i| i.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            InputType::String,
+            InputType::Bool,
+            InputType::Select,
+            InputType::Int
+        ]
+    );
+    assert_eq!(inputs[0].default, Some(json!("com.example.{{project}}")));
+    assert_eq!(inputs[0].validate.as_deref(), Some("^[a-z.]+$"));
+    assert_eq!(inputs[2].options().unwrap(), ["gradle", "maven"]);
+    assert!(inputs[3].options().is_err());
+}
+
+#[test]
+fn defaults() {
+    assert_eq!(value_to_int(&json!(8080)).unwrap(), 8080);
+    assert_eq!(value_to_int(&json!("8080")).unwrap(), 8080);
+    assert!(value_to_int(&json!("eighty")).is_err());
+    assert!(value_to_bool(&json!(true)).unwrap());
+    assert!(!value_to_bool(&json!("false")).unwrap());
+    assert!(value_to_bool(&json!("nope")).is_err());
+}
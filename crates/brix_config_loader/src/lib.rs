@@ -9,31 +9,76 @@
 //! the declaration file into a parsed list of [commands](`brix_commands::Command`) and arguments.
 
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
-use std::fs;
 use std::path::PathBuf;
 
+mod compose;
+mod declarations;
+mod generate;
+mod inputs;
 mod parsers;
 mod process;
+pub use declarations::{module_dir, modules_from_config, search_for_module_declarations};
+pub use generate::ConfigGenerator;
+pub use inputs::{InputType, RawInput};
 use parsers::ConfigParser;
-pub use parsers::YamlConfigParser;
+pub use parsers::{JsonConfigParser, TomlConfigParser, YamlConfigParser};
 
 use brix_cli::select::do_select;
-use brix_commands::{Command, ProcessedCommandParams};
+use brix_commands::{Command, CommandParams, CommandRegistry, GenerateCommand};
+use brix_commands::{
+    CopyCommand, ExecCommand, InjectCommand, MkdirCommand, MoveCommand, RemoveCommand,
+    SearchReplaceCommand, SymlinkCommand, TemplateCommand, WriteCommand,
+};
+use brix_common::context::Positional;
 use brix_common::AppContext;
 use brix_errors::BrixError;
 
 #[allow(rustdoc::private_intra_doc_links)]
 /// Defines a parser list as a vec of trait objects implementing [ConfigParser].
 pub type ParserList = Vec<Box<dyn ConfigParser>>;
-/// Defines a command list as a vec of tuples containing a [Command] trait object and [ProcessedCommandParams].
-type CommandList = Vec<(Box<dyn Command>, ProcessedCommandParams)>;
+/// Returns the parsers for every supported config format.
+pub fn default_parsers() -> ParserList {
+    vec![
+        Box::new(YamlConfigParser {}),
+        Box::new(TomlConfigParser {}),
+        Box::new(JsonConfigParser {}),
+    ]
+}
+
+/// Returns a registry with every built in command.
+pub fn default_registry() -> CommandRegistry {
+    let mut registry = CommandRegistry::new();
+    registry.register("copy", |_| Box::new(CopyCommand::new()));
+    registry.register("exec", |_| Box::new(ExecCommand::new()));
+    registry.register("generate", |registry| {
+        Box::new(GenerateCommand::new(Box::new(ConfigGenerator::new(
+            registry.clone(),
+        ))))
+    });
+    registry.register("inject", |_| Box::new(InjectCommand::new()));
+    registry.register("mkdir", |_| Box::new(MkdirCommand::new()));
+    registry.register("move", |_| Box::new(MoveCommand::new()));
+    registry.register("remove", |_| Box::new(RemoveCommand::new()));
+    registry.register("search_replace", |_| Box::new(SearchReplaceCommand::new()));
+    registry.register("symlink", |_| Box::new(SymlinkCommand::new()));
+    registry.register("template", |_| Box::new(TemplateCommand::new()));
+    registry.register("write", |_| Box::new(WriteCommand::new()));
+    registry
+}
+
+/// Defines a command list as a vec of tuples containing a [Command] trait object and [CommandParams].
+pub type CommandList = Vec<(Box<dyn Command>, CommandParams)>;
 
 /// Struct that holds current information about the loaded configs and parsers.
 pub struct ConfigLoader<'a> {
     parsers: ParserList,
     config_file: Option<PathBuf>,
     config_dir: Option<PathBuf>,
+    context: Option<HashMap<String, Value>>,
+    answers: Option<HashMap<String, Value>>,
+    registry: CommandRegistry,
     cli_config: &'a brix_cli::Config,
 }
 
@@ -44,11 +89,21 @@ impl<'a> ConfigLoader<'a> {
             cli_config,
             config_file: None,
             config_dir: None,
+            context: None,
+            answers: None,
+            registry: default_registry(),
         }
     }
 
+    /// Uses the commands in the registry instead of the built in ones.
+    pub fn with_registry(mut self, registry: CommandRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
     /// Selects the proper configuration file knowing the supported extension for config files.
-    /// May possibly use a [select prompt](`brix_cli::select::do_select`) to ask the user to choose a config file.
+    /// May possibly use a [select prompt](`brix_cli::select::do_select`) to ask the user to choose a config file,
+    /// which fails when prompts are disabled.
     pub fn load(&mut self, config_files: Vec<PathBuf>) -> Result<PathBuf, BrixError> {
         self.config_dir = Some(config_files[0].parent().unwrap().to_path_buf());
         let mut all_extensions = Vec::new();
@@ -78,6 +133,14 @@ impl<'a> ConfigLoader<'a> {
                 .map(|f| f.file_name().unwrap().to_str().unwrap())
                 .collect();
 
+            // There's no sensible default between two configs with the same name
+            if !self.cli_config.is_interactive() {
+                return Err(BrixError::with(&format!(
+                    "found multiple config files ({}) and prompts are disabled, remove all but one of them",
+                    names.join(", ")
+                )));
+            }
+
             println!("found multiple config files, which one would you like to use?");
             let result = do_select(names)?;
 
@@ -90,23 +153,29 @@ impl<'a> ConfigLoader<'a> {
     #[allow(rustdoc::private_intra_doc_links)]
     /// Actually parsers the config file for errors depending on the correct parser for the file.
     /// Sends resulting parsed output to [process] to be processed into commands.
-    pub fn run(&self, app_context: &AppContext) -> Result<CommandList, BrixError> {
-        let mut parser: Option<&Box<dyn ConfigParser>> = None;
-
-        // Loop over each of the valid parsers this instance is configured with and see
-        // if the extension for the config file matches with the parser we are testing
-        for parser_opt in self.parsers.iter() {
-            if parser_opt.matches(self.config_file.as_ref().unwrap()) {
-                parser = Some(parser_opt);
-                break;
-            }
-        }
-
-        // Read the contents of the file to a string and parse it into the raw struct
-        let contents = fs::read_to_string(self.config_file.as_ref().unwrap())?;
-        let config = parser.unwrap().parse(&contents)?;
+    pub fn run(&mut self, app_context: &AppContext) -> Result<CommandList, BrixError> {
+        let config_file = self.config_file.as_ref().unwrap();
+        // Parse the file into the raw struct and merge in anything it extends or includes (./compose.rs)
+        let config = self.parse_file(config_file)?;
+        let config = self.compose(config, config_file, &mut vec![])?;
+        // Ask for any declared inputs before processing (./inputs.rs)
+        let inputs = self.ask_inputs(&config, app_context)?;
+        self.context = Some(self.resolve_context(&config, &inputs, app_context)?);
+        self.answers = Some(self.resolve_answers(&config, &inputs)?);
         // Send it over to be processed (./process.rs)
-        self.process(&config, app_context)
+        self.process(&config, &inputs)
+    }
+
+    /// The context shared by every command of the last [run](ConfigLoader::run),
+    /// without the context of the commands themselves.
+    pub fn context(&self) -> Option<&HashMap<String, Value>> {
+        self.context.as_ref()
+    }
+
+    /// The arguments, inputs and overrides of the last [run](ConfigLoader::run),
+    /// which `brix update` passes back as overrides to run the config again.
+    pub fn answers(&self) -> Option<&HashMap<String, Value>> {
+        self.answers.as_ref()
     }
 }
 
@@ -114,28 +183,40 @@ impl<'a> ConfigLoader<'a> {
 /// of a Vec of HashMaps.
 #[derive(Debug)]
 struct Config {
-    context: Option<HashMap<String, String>>,
+    context: Option<HashMap<String, Value>>,
     commands: Vec<(String, RawCommandParams)>,
 }
 
 /// The raw struct used for an entire config.
 /// Accurately describes the fields in a valid config file.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct RawConfig {
-    context: Option<HashMap<String, String>>,
+    extends: Option<String>,
+    include: Option<Vec<String>>,
+    args: Option<Vec<Positional>>,
+    inputs: Option<Vec<RawInput>>,
+    context: Option<HashMap<String, Value>>,
+    #[serde(default)]
     commands: Vec<HashMap<String, RawCommandParams>>,
 }
 
+impl RawConfig {
+    /// The declared positional arguments, or `project` and `module` if none are declared.
+    fn positionals(&self) -> Vec<Positional> {
+        self.args.clone().unwrap_or_else(Positional::defaults)
+    }
+}
+
 /// The raw output for any config parser.
-/// Defines all fields and their inital (not preferred) types for all commands.
+/// The fields every command supports are handled by the loader,
+/// the rest are templated and handed over to the command to parse.
 #[derive(Serialize, Deserialize, Debug)]
 struct RawCommandParams {
-    source: Option<String>,
-    destination: Option<String>,
-    overwrite: Option<bool>,
-    search: Option<String>,
-    replace: Option<String>,
-    commands: Option<Vec<String>>,
-    stdout: Option<bool>,
-    context: Option<HashMap<String, String>>,
+    when: Option<String>,
+    for_each: Option<Value>,
+    #[serde(rename = "as")]
+    alias: Option<String>,
+    context: Option<HashMap<String, Value>>,
+    #[serde(flatten)]
+    params: Map<String, Value>,
 }
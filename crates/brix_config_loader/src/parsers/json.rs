@@ -0,0 +1,39 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! JSON config parser.
+
+use std::path::PathBuf;
+
+use crate::parsers::ConfigParser;
+use crate::BrixError;
+use crate::RawConfig;
+
+#[cfg(test)]
+mod tests {
+    mod parse;
+}
+
+/// The config parser for .json files.
+/// A top-level `$schema` key is ignored so editors can point it at `schemas/command.json`.
+pub struct JsonConfigParser;
+
+const SUPPORTED_EXTENSIONS: [&str; 1] = ["json"];
+
+impl ConfigParser for JsonConfigParser {
+    fn parse(&self, contents: &str) -> Result<RawConfig, BrixError> {
+        let config: RawConfig = serde_json::from_str(contents)?;
+        Ok(config)
+    }
+
+    fn matches(&self, path: &PathBuf) -> bool {
+        let ext = path.extension().unwrap();
+        SUPPORTED_EXTENSIONS.contains(&ext.to_str().unwrap())
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        SUPPORTED_EXTENSIONS.to_vec()
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::path::PathBuf;
+
+use crate::parsers::{ConfigParser, JsonConfigParser};
+
+#[test]
+fn matches() {
+    let parser = JsonConfigParser {};
+    assert!(parser.matches(&PathBuf::from("foo.brix.json")));
+    assert!(!parser.matches(&PathBuf::from("foo.brix.toml")));
+}
+
+#[test]
+fn schema() {
+    let config = JsonConfigParser {}
+        .parse(
+            r#"{
+  "$schema": "../../../schemas/command.json",
+  "context": { "foo": "bar" },
+  "commands": [
+    { "mkdir": { "destination": "output/{{foo}}" } }
+  ]
+}"#,
+        )
+        .unwrap();
+
+    let mkdir = config.commands[0].get("mkdir").unwrap();
+    assert_eq!(mkdir.params["destination"], "output/{{foo}}");
+    assert_eq!(config.context.unwrap()["foo"], "bar");
+}
+
+#[test]
+fn line_and_column() {
+    let err = JsonConfigParser {}
+        .parse("{\n  \"commands\": [\n    { \"mkdir\": }\n  ]\n}")
+        .unwrap_err();
+    assert!(err.message.contains("line 3 column 16"), "{}", err.message);
+}
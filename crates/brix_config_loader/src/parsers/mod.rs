@@ -9,7 +9,11 @@ use crate::BrixError;
 use crate::RawConfig;
 use std::path::PathBuf;
 
+mod json;
+mod toml;
 mod yaml;
+pub use self::toml::TomlConfigParser;
+pub use json::JsonConfigParser;
 pub use yaml::YamlConfigParser;
 
 /// Trait that all config parsers must implement.
@@ -0,0 +1,38 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! TOML config parser.
+
+use std::path::PathBuf;
+
+use crate::parsers::ConfigParser;
+use crate::BrixError;
+use crate::RawConfig;
+
+#[cfg(test)]
+mod tests {
+    mod parse;
+}
+
+/// The config parser for .toml files.
+pub struct TomlConfigParser;
+
+const SUPPORTED_EXTENSIONS: [&str; 1] = ["toml"];
+
+impl ConfigParser for TomlConfigParser {
+    fn parse(&self, contents: &str) -> Result<RawConfig, BrixError> {
+        let config: RawConfig = toml::from_str(contents)?;
+        Ok(config)
+    }
+
+    fn matches(&self, path: &PathBuf) -> bool {
+        let ext = path.extension().unwrap();
+        SUPPORTED_EXTENSIONS.contains(&ext.to_str().unwrap())
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        SUPPORTED_EXTENSIONS.to_vec()
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use std::path::PathBuf;
+
+use crate::parsers::{ConfigParser, TomlConfigParser, YamlConfigParser};
+use serde_json::json;
+
+const TOML_CONFIG: &str = r#"
+[context]
+foo = "bar"
+
+[[commands]]
+[commands.copy]
+source = "test.rs.txt"
+destination = "output/{{module}}/{{foo}}.rs"
+overwrite = true
+
+[[commands]]
+[commands.exec]
+commands = ["cargo --version"]
+stdout = false
+"#;
+
+#[test]
+fn matches() {
+    let parser = TomlConfigParser {};
+    assert!(parser.matches(&PathBuf::from("foo.brix.toml")));
+    assert!(!parser.matches(&PathBuf::from("foo.brix.yml")));
+}
+
+#[test]
+fn commands() {
+    let config = TomlConfigParser {}.parse(TOML_CONFIG).unwrap();
+    let keys: Vec<&String> = config
+        .commands
+        .iter()
+        .map(|c| c.keys().next().unwrap())
+        .collect();
+    assert_eq!(keys, [&"copy", &"exec"]);
+
+    let copy = config.commands[0].get("copy").unwrap();
+    assert_eq!(copy.params["source"], "test.rs.txt");
+    assert_eq!(copy.params["overwrite"], true);
+    let exec = config.commands[1].get("exec").unwrap();
+    assert_eq!(exec.params["commands"], json!(["cargo --version"]));
+    assert_eq!(exec.params["stdout"], false);
+}
+
+#[test]
+fn same_as_yaml() {
+    let yaml = r#"
+context:
+  foo: bar
+commands:
+  - copy:
+      source: test.rs.txt
+      destination: "output/{{module}}/{{foo}}.rs"
+      overwrite: true
+  - exec:
+      commands:
+        - cargo --version
+      stdout: false
+"#;
+    let from_toml = TomlConfigParser {}.parse(TOML_CONFIG).unwrap();
+    let from_yaml = YamlConfigParser {}.parse(yaml).unwrap();
+    assert_eq!(format!("{:?}", from_toml), format!("{:?}", from_yaml));
+}
+
+#[test]
+fn invalid() {
+    assert!(TomlConfigParser {}.parse("[[commands]\n").is_err());
+}
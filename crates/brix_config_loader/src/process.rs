@@ -5,159 +5,324 @@
 
 //! Module responsible for processing a [RawConfig] into a list of commands.
 
-use lazy_static::lazy_static;
-use serde_json::json;
+use log::info;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use brix_commands::{
-    CopyCommand, ExecCommand, MkdirCommand, SearchReplaceCommand, TemplateCommand,
+    Command, CommandFactory, CommandParams, CommandRegistry, PlannedAction, PluginCommand,
 };
-use brix_common::context::{cli_config_to_map, ContextMap};
+use brix_common::context::{cli_config_to_map, merge_into, ContextMap};
 use brix_common::AppContext;
 use brix_errors::BrixError;
 
-use crate::ConfigLoader;
-use crate::{Command, CommandList, RawConfig};
-use crate::{ProcessedCommandParams, RawCommandParams};
+use crate::{CommandList, ConfigLoader, RawConfig};
 
-lazy_static! {
-    static ref SUPPORTED_COMMANDS: Vec<&'static str> =
-        vec!["copy", "exec", "mkdir", "search_replace", "template"];
+#[cfg(test)]
+mod tests {
+    mod for_each;
+    mod params;
+}
+
+/// The directory plugins are searched for in before the `PATH`, next to the language directories.
+const PLUGIN_DIR: &str = "plugins";
+
+/// Resolves the items of a `for_each`, which is either a literal list
+/// or the name of a list in the context such as `entities` or `{{db.tables}}`.
+fn for_each_items(
+    for_each: &Value,
+    context: &HashMap<String, Value>,
+) -> Result<Vec<Value>, BrixError> {
+    let name = match for_each {
+        Value::Array(items) => return Ok(items.clone()),
+        Value::String(name) => name
+            .trim()
+            .trim_start_matches("{{")
+            .trim_end_matches("}}")
+            .trim(),
+        other => {
+            return Err(BrixError::with(&format!(
+                "for_each must be a list or the name of a list in the context, got '{}'",
+                other
+            )))
+        }
+    };
+
+    let mut parts = name.split('.');
+    let mut current = context.get(parts.next().unwrap());
+    for part in parts {
+        current = current.and_then(|value| value.get(part));
+    }
+
+    match current {
+        Some(Value::Array(items)) => Ok(items.clone()),
+        Some(other) => Err(BrixError::with(&format!(
+            "for_each context '{}' is not a list: '{}'",
+            name, other
+        ))),
+        None => Err(BrixError::with(&format!(
+            "for_each context '{}' does not exist",
+            name
+        ))),
+    }
+}
+
+/// A command of the config whose params are templated right before it runs instead of when the
+/// config is processed, so they can use the context exported by plugins that ran before it.
+/// Its `when` and `for_each` are only evaluated then too.
+struct TemplatedCommand {
+    command: Box<dyn Command>,
+    key: String,
+    when: Option<String>,
+    for_each: Option<Value>,
+    alias: Option<String>,
+    /// The context declared on the command.
+    local_context: HashMap<String, Value>,
+    /// Every other layer of the context, without the exports.
+    context_map: ContextMap,
+    config_dir: Option<PathBuf>,
+}
+
+impl TemplatedCommand {
+    /// The params for each time the command runs, templated with the context as it is now.
+    /// Iterations whose condition is false are left out.
+    fn iterations(
+        &self,
+        params: CommandParams,
+        app_context: &AppContext,
+    ) -> Result<Vec<CommandParams>, BrixError> {
+        let json = params.into_value();
+        let context_map = |command_local| {
+            let mut context_map = self.context_map.clone();
+            context_map
+                .config_global
+                .extend(app_context.exports.borrow().clone());
+            context_map.command_local = command_local;
+            context_map
+        };
+
+        // Each item in `for_each` is its own command with the item and index in the local context
+        let iterations = match &self.for_each {
+            Some(for_each) => {
+                let context =
+                    template_context(context_map(self.local_context.clone()), app_context)?;
+                for_each_items(for_each, &context)?
+                    .into_iter()
+                    .enumerate()
+                    .map(Some)
+                    .collect()
+            }
+            None => vec![None],
+        };
+
+        let mut list = Vec::new();
+        for iteration in iterations {
+            let mut local_context = self.local_context.clone();
+            if let Some((index, item)) = iteration {
+                let name = self.alias.clone().unwrap_or_else(|| String::from("item"));
+                local_context.insert(name, item);
+                local_context.insert(String::from("index"), Value::from(index));
+            }
+            let processed_processor_context =
+                template_context(context_map(local_context), app_context)?;
+
+            // Leave out the command entirely if its condition is false
+            if let Some(condition) = &self.when {
+                let condition_context =
+                    brix_processor::create_context(processed_processor_context.clone());
+                if !app_context
+                    .processor
+                    .evaluate(condition, condition_context)?
+                {
+                    info!("skipping {} command, '{}' is false", self.key, condition);
+                    continue;
+                }
+            }
+
+            // Each string is rendered on its own, so values can't break out of the JSON
+            let processed = app_context.processor.process_value(
+                json.clone(),
+                &brix_processor::create_context(processed_processor_context.clone()),
+            )?;
+            let mut params: serde_json::Map<String, Value> = serde_json::from_value(processed)?;
+            // Sources are relative to the config
+            if let Some(Value::String(source)) = params.get("source") {
+                let source = self.config_dir.as_ref().unwrap().join(source);
+                params.insert(String::from("source"), json!(source));
+            }
+            // Commands render with the whole context, while the context declared on the command
+            // is kept apart for commands that pass it on, like `generate`
+            let declared = template_local(
+                self.local_context.clone(),
+                &processed_processor_context,
+                app_context,
+            )?;
+            params.insert(String::from("local_context"), json!(declared));
+            params.insert(String::from("context"), json!(processed_processor_context));
+
+            list.push(CommandParams::new(Value::Object(params)));
+        }
+        Ok(list)
+    }
+}
+
+impl Command for TemplatedCommand {
+    fn run(&self, params: CommandParams, app_context: &AppContext) -> Result<(), BrixError> {
+        for params in self.iterations(params, app_context)? {
+            self.command.run(params, app_context)?;
+        }
+        Ok(())
+    }
+
+    fn plan(
+        &self,
+        params: CommandParams,
+        app_context: &AppContext,
+    ) -> Result<Vec<PlannedAction>, BrixError> {
+        let mut actions = Vec::new();
+        for params in self.iterations(params, app_context)? {
+            actions.extend(self.command.plan(params, app_context)?);
+        }
+        Ok(actions)
+    }
+
+    fn name(&self) -> String {
+        self.command.name()
+    }
 }
 
 impl<'a> ConfigLoader<'a> {
     /// Converts the [RawConfig] into a list of commands or returns an error.
+    /// The params of each command are templated once it runs, see [TemplatedCommand].
     pub fn process(
         &self,
         config: &RawConfig,
-        app_context: &AppContext,
+        inputs: &HashMap<String, Value>,
     ) -> Result<CommandList, BrixError> {
         let mut list = CommandList::new();
+        let context_map = ContextMap {
+            cli_positional: cli_config_to_map(self.cli_config, &config.positionals())?,
+            inputs: inputs.clone(),
+            config_global: config.context.clone().unwrap_or_default(),
+            command_local: HashMap::new(),
+            cli_overrides: self.cli_overrides(config)?,
+        };
 
         for command in config.commands.iter() {
             let key = command.keys().next().unwrap();
             let value = command.values().next().unwrap();
-            let command: Box<dyn Command> = match key.to_lowercase().as_str() {
-                "copy" => Box::new(CopyCommand::new()),
-                "exec" => Box::new(ExecCommand::new()),
-                "mkdir" => Box::new(MkdirCommand::new()),
-                "search_replace" => Box::new(SearchReplaceCommand::new()),
-                "template" => Box::new(TemplateCommand::new()),
-                _ => {
-                    let matches =
-                        difflib::get_close_matches(key, SUPPORTED_COMMANDS.to_vec(), 1, 0.6);
-                    if let Some(closest) = matches.get(0) {
-                        return Err(BrixError::with(&format!(
-                            "command '{}' not found... did you mean '{}'?",
-                            key, closest
-                        )));
-                    } else {
-                        return Err(BrixError::with(&format!("command '{}' not found", key)));
-                    }
-                }
-            };
+            let create_command = self.factory(key)?;
 
-            // Serialize the data into json
-            let json = json!(value);
-            // Read context
-            let local_context = value.context.clone().unwrap_or(HashMap::new());
-            // Create context map and populate accordingly
-            let context_map = ContextMap {
-                cli_positional: cli_config_to_map(self.cli_config),
-                config_global: config.context.clone().unwrap_or(HashMap::new()),
-                command_local: local_context,
+            let templated = TemplatedCommand {
+                command: create_command(&self.registry),
+                key: key.clone(),
+                when: value.when.clone(),
+                for_each: value.for_each.clone(),
+                alias: value.alias.clone(),
+                local_context: value.context.clone().unwrap_or_default(),
+                context_map: context_map.clone(),
+                config_dir: self.config_dir.clone(),
             };
-            // Merge contexts together
-            let context = context_map.do_merge();
-
-            // After the merge, template the actual context itself in case it includes context
-            // For instance, the context might be something like `path: temp/{{module}}`
-            let processor_context = brix_processor::create_context(context.clone());
-            let mut processed_processor_context = HashMap::new();
-            // TODO: perhaps templating each individual context line isn't really that performant...
-            for (key, raw_line) in processor_context.iter() {
-                // Replace the raw quotation marks
-                let context_line = raw_line.to_string().replace("\"", "");
-                let processed = app_context
-                    .processor
-                    .process(context_line, processor_context.clone())?;
-                processed_processor_context.insert(String::from(key), processed);
-            }
-
-            let res = app_context.processor.process(
-                json.to_string(),
-                brix_processor::create_context(processed_processor_context.clone()),
-            )?;
-            let raw_args: RawCommandParams = serde_json::from_str(&res).unwrap();
-            let mut args = self.create_processed_args(&raw_args)?;
-            args.context = Some(processed_processor_context);
-
-            list.push((command, args));
+            // Serialize the params into json, leaving out the fields handled here
+            list.push((
+                Box::new(templated) as Box<dyn Command>,
+                CommandParams::new(json!(value.params)),
+            ));
         }
 
         Ok(list)
     }
 
-    /// Formats all the raw types (such as strings) into their preferred types
-    /// for the given field (such as a path).
-    fn create_processed_args(
-        &self,
-        raw: &RawCommandParams,
-    ) -> Result<ProcessedCommandParams, BrixError> {
-        macro_rules! lf {
-            ($val:expr) => {{
-                let mut val = $val;
-                val = val.replace("\\n", "\n");
-                val.replace("\\t", "\t")
-            }};
-        }
-
-        let config = self.config_dir.as_ref().unwrap();
+    /// Returns the factory for the command, falling back to a `brix-<name>` plugin
+    /// in the `plugins` directory next to the language directory or on the `PATH`.
+    fn factory(&self, name: &str) -> Result<CommandFactory, BrixError> {
+        self.registry.factory(name).or_else(|err| {
+            let plugin_dir = self
+                .config_dir
+                .as_ref()
+                .and_then(|dir| dir.parent())
+                .map(|dir| dir.join(PLUGIN_DIR));
+            match PluginCommand::find(name, plugin_dir.as_deref()) {
+                Some(plugin) => {
+                    let factory: CommandFactory = Rc::new(move |_: &CommandRegistry| {
+                        Box::new(plugin.clone()) as Box<dyn Command>
+                    });
+                    Ok(factory)
+                }
+                None => Err(err),
+            }
+        })
+    }
 
-        let mut source = None;
-        let mut destination = None;
-        let mut overwrite = None;
-        let mut search = None;
-        let mut replace = None;
-        let mut commands = None;
-        let mut stdout = None;
-        let mut context = None;
+    /// Merges and templates the context shared by every command of the config.
+    pub(crate) fn resolve_context(
+        &self,
+        config: &RawConfig,
+        inputs: &HashMap<String, Value>,
+        app_context: &AppContext,
+    ) -> Result<HashMap<String, Value>, BrixError> {
+        let context_map = ContextMap {
+            cli_positional: cli_config_to_map(self.cli_config, &config.positionals())?,
+            inputs: inputs.clone(),
+            config_global: config.context.clone().unwrap_or_default(),
+            command_local: HashMap::new(),
+            cli_overrides: self.cli_overrides(config)?,
+        };
+        template_context(context_map, app_context)
+    }
 
-        if let Some(raw_source) = &raw.source {
-            source = Some(config.join(raw_source)); // Source is relative to config
-        }
-        if let Some(raw_destination) = &raw.destination {
-            destination = Some(PathBuf::from(raw_destination)); // Dest is absolute path
-        }
-        if let Some(raw_overwrite) = raw.overwrite {
-            overwrite = Some(raw_overwrite);
-        }
-        if let Some(raw_search) = &raw.search {
-            search = Some(lf!(raw_search.clone()));
-        }
-        if let Some(raw_replace) = &raw.replace {
-            replace = Some(lf!(raw_replace.clone()));
-        }
-        if let Some(raw_commands) = &raw.commands {
-            commands = Some(raw_commands.clone());
-        }
-        if let Some(raw_stdout) = &raw.stdout {
-            stdout = Some(raw_stdout.clone());
-        }
-        if let Some(raw_context) = &raw.context {
-            context = Some(raw_context.clone());
+    /// Merges everything that was answered on the command line or when asked,
+    /// without the language which is stored alongside it.
+    pub(crate) fn resolve_answers(
+        &self,
+        config: &RawConfig,
+        inputs: &HashMap<String, Value>,
+    ) -> Result<HashMap<String, Value>, BrixError> {
+        let mut answers = cli_config_to_map(self.cli_config, &config.positionals())?;
+        answers.remove("language");
+        answers.extend(inputs.clone());
+        for (key, value) in self.cli_overrides(config)?.into_iter() {
+            merge_into(&mut answers, key, value);
         }
+        Ok(answers)
+    }
+}
 
-        Ok(ProcessedCommandParams {
-            source,
-            destination,
-            overwrite,
-            search,
-            replace,
-            commands,
-            stdout,
-            context,
-        })
+/// Templates the context declared on a command with the merged context of the command.
+fn template_local(
+    local_context: HashMap<String, Value>,
+    context: &HashMap<String, Value>,
+    app_context: &AppContext,
+) -> Result<HashMap<String, Value>, BrixError> {
+    let processor_context = brix_processor::create_context(context.clone());
+    let mut processed = HashMap::new();
+    for (key, raw_value) in local_context.into_iter() {
+        let value = app_context
+            .processor
+            .process_value(raw_value, &processor_context)?;
+        processed.insert(key, value);
+    }
+    Ok(processed)
+}
+
+/// Merges the context map and templates the result in case it includes context.
+/// For instance, the context might be something like `path: temp/{{module}}`.
+/// Only the string leaves are templated, lists and nested maps keep their shape.
+fn template_context(
+    context_map: ContextMap,
+    app_context: &AppContext,
+) -> Result<HashMap<String, Value>, BrixError> {
+    let context = context_map.do_merge();
+    let processor_context = brix_processor::create_context(context);
+    let mut processed_processor_context = HashMap::new();
+    // TODO: perhaps templating each individual context line isn't really that performant...
+    for (key, raw_value) in processor_context.iter() {
+        let processed = app_context
+            .processor
+            .process_value(raw_value.clone(), &processor_context)?;
+        processed_processor_context.insert(String::from(key), processed);
     }
+    Ok(processed_processor_context)
 }
@@ -0,0 +1,66 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::parsers::{ConfigParser, YamlConfigParser};
+use crate::process::for_each_items;
+
+fn context() -> HashMap<String, Value> {
+    let mut context = HashMap::new();
+    context.insert(String::from("entities"), json!(["user", "order"]));
+    context.insert(String::from("db"), json!({"tables": [{"name": "users"}]}));
+    context.insert(String::from("name"), json!("brix"));
+    context
+}
+
+#[test]
+fn parse() {
+    let config = YamlConfigParser {}
+        .parse(
+            r#"
+commands:
+  - template:
+      for_each: entities
+      as: entity
+      source: entity.hbs
+      destination: src/{{entity}}.rs
+"#,
+        )
+        .unwrap();
+    let params = config.commands[0].get("template").unwrap();
+    assert_eq!(params.for_each, Some(json!("entities")));
+    assert_eq!(params.alias, Some(String::from("entity")));
+}
+
+#[test]
+fn literal_list() {
+    let items = for_each_items(&json!(["a", "b", "c"]), &context()).unwrap();
+    assert_eq!(items, vec![json!("a"), json!("b"), json!("c")]);
+}
+
+#[test]
+fn context_list() {
+    let items = for_each_items(&json!("entities"), &context()).unwrap();
+    assert_eq!(items, vec![json!("user"), json!("order")]);
+
+    let items = for_each_items(&json!("{{ db.tables }}"), &context()).unwrap();
+    assert_eq!(items, vec![json!({"name": "users"})]);
+}
+
+#[test]
+fn invalid() {
+    let err = for_each_items(&json!("missing"), &context()).unwrap_err();
+    assert_eq!(err.message, "for_each context 'missing' does not exist");
+
+    let err = for_each_items(&json!("name"), &context()).unwrap_err();
+    assert_eq!(
+        err.message,
+        "for_each context 'name' is not a list: '\"brix\"'"
+    );
+
+    assert!(for_each_items(&json!(3), &context()).is_err());
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::parsers::ConfigParser;
+use crate::{ConfigLoader, YamlConfigParser};
+use brix_common::{AppContext, Filesystem, MemoryFilesystem};
+use brix_processor::ProcessorCore;
+
+/// Runs a `write` of the context value in memory and returns what was written.
+fn write(value: Value) -> String {
+    let cli_config = brix_cli::Config::default();
+    let loader = ConfigLoader::new(vec![Box::new(YamlConfigParser {})], &cli_config);
+    let config = YamlConfigParser {}
+        .parse(
+            r#"
+args: []
+commands:
+  - write:
+      destination: value.txt
+      content: "{{value}}"
+"#,
+        )
+        .unwrap();
+    let mut inputs = HashMap::new();
+    inputs.insert(String::from("value"), value);
+
+    let fs = Rc::new(MemoryFilesystem::new());
+    let context = AppContext::new(ProcessorCore::new(), &cli_config).with_filesystem(fs.clone());
+    for (command, params) in loader.process(&config, &inputs).unwrap() {
+        command.run(params, &context).unwrap();
+    }
+    fs.read_to_string(&cli_config.workdir.join("value.txt"))
+        .unwrap()
+}
+
+#[test]
+fn multiline() {
+    assert_eq!(write(json!("first\nsecond")), "first\nsecond");
+}
+
+#[test]
+fn backslash() {
+    assert_eq!(write(json!(r"C:\new\table")), r"C:\new\table");
+}
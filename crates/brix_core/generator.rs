@@ -0,0 +1,298 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [Generator], which runs a config from Rust instead of the command line.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
+
+use colored::*;
+use log::{debug, info};
+
+use brix_cli::{OverwritePolicy, Prompts};
+use brix_commands::CommandRegistry;
+use brix_common::answers::{Answers, ConfigAnswers};
+use brix_common::history::Manifest;
+use brix_common::{AppContext, DiskFilesystem, Filesystem};
+use brix_config_loader::{
+    default_parsers, default_registry, module_dir, modules_from_config, CommandList, ConfigLoader,
+};
+use brix_errors::BrixError;
+use brix_processor::ProcessorCore;
+
+use crate::report::{CommandReport, RunReport};
+use crate::util::display_path;
+
+#[cfg(test)]
+mod tests {
+    mod run;
+}
+
+/// Runs a config the same way as the `brix` binary.
+///
+/// ```no_run
+/// use serde_json::json;
+///
+/// let report = brix::Generator::new("java", "controller")
+///     .config_dir("templates")
+///     .workdir("service")
+///     .context_value("module", json!("users"))
+///     .run()?;
+/// println!("changed {} path(s)", report.changes.len());
+/// # Ok::<(), brix_errors::BrixError>(())
+/// ```
+pub struct Generator {
+    config: brix_cli::Config,
+    registry: CommandRegistry,
+    fs: Rc<dyn Filesystem>,
+}
+
+impl Generator {
+    /// Creates the generator for the config name in the language directory.
+    /// Prompts are answered with their defaults unless [prompts](Generator::prompts) says otherwise.
+    pub fn new(language: &str, config_name: &str) -> Self {
+        Self::from_config(brix_cli::Config {
+            language: language.to_string(),
+            config_name: config_name.to_string(),
+            home_dir: home::home_dir(),
+            prompts: Prompts::Defaults,
+            ..Default::default()
+        })
+    }
+
+    /// Creates the generator from a config, such as the one the CLI creates from its arguments.
+    pub fn from_config(config: brix_cli::Config) -> Self {
+        Self {
+            config,
+            registry: default_registry(),
+            fs: Rc::new(DiskFilesystem),
+        }
+    }
+
+    /// The directory containing the language directories, otherwise `.config/brix`
+    /// is searched for in the working directory, its parents and the home directory.
+    pub fn config_dir<P: Into<PathBuf>>(mut self, config_dir: P) -> Self {
+        self.config.config_dir = Some(config_dir.into());
+        self
+    }
+
+    /// The directory commands run in, defaults to the current directory.
+    pub fn workdir<P: Into<PathBuf>>(mut self, workdir: P) -> Self {
+        self.config.workdir = workdir.into();
+        self
+    }
+
+    /// Adds every value to the context the same way as `--set-json`,
+    /// which also answers arguments and inputs of the same name.
+    pub fn context(mut self, context: HashMap<String, Value>) -> Self {
+        let mut context: Vec<(String, Value)> = context.into_iter().collect();
+        context.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.config.overrides.extend(context);
+        self
+    }
+
+    /// Adds a single value to the context, see [context](Generator::context).
+    pub fn context_value(mut self, key: &str, value: Value) -> Self {
+        self.config.overrides.push((key.to_string(), value));
+        self
+    }
+
+    /// The policy for commands that don't set `overwrite` themselves.
+    pub fn overwrite(mut self, policy: OverwritePolicy) -> Self {
+        self.config.overwrite = Some(policy);
+        self
+    }
+
+    /// How prompts are answered.
+    pub fn prompts(mut self, prompts: Prompts) -> Self {
+        self.config.prompts = prompts;
+        self
+    }
+
+    /// Uses the commands in the registry instead of the built in ones.
+    pub fn registry(mut self, registry: CommandRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// The filesystem that commands, the history and the answers are read from and written to.
+    /// With a [MemoryFilesystem](brix_common::MemoryFilesystem) nothing is changed on the disk,
+    /// except by `exec` commands and plugins.
+    pub fn filesystem(mut self, fs: Rc<dyn Filesystem>) -> Self {
+        self.fs = fs;
+        self
+    }
+
+    /// Describes what every command would do without changing anything.
+    pub fn plan(&self) -> Result<RunReport, BrixError> {
+        let start = Instant::now();
+        let mut loader = self.loader();
+        let config_file = loader.load(self.declarations()?)?;
+        let app_context =
+            AppContext::new(ProcessorCore::new(), &self.config).with_filesystem(self.fs.clone());
+        let commands = self.load_commands(&mut loader, &app_context, &config_file)?;
+
+        let mut reports = Vec::new();
+        for (command, params) in commands.into_iter() {
+            reports.push(CommandReport {
+                name: command.name(),
+                actions: command.plan(params, &app_context)?,
+            });
+        }
+
+        Ok(RunReport {
+            config_file,
+            commands: reports,
+            changes: vec![],
+            context: loader.context().cloned().unwrap_or_default(),
+            manifest: None,
+            elapsed: start.elapsed(),
+        })
+    }
+
+    /// Runs every command of the config. If one fails, everything that was changed
+    /// is rolled back unless rollback is disabled, and the error is returned.
+    /// Records the run in `.brix/history` and the answers in `.brix-answers.yml`.
+    pub fn run(&self) -> Result<RunReport, BrixError> {
+        let config = &self.config;
+        let start = Instant::now();
+        let mut loader = self.loader();
+        let config_file = loader.load(self.declarations()?)?;
+        let app_context =
+            AppContext::new(ProcessorCore::new(), config).with_filesystem(self.fs.clone());
+        let commands = self.load_commands(&mut loader, &app_context, &config_file)?;
+
+        info!(
+            "{} {}",
+            "CONFIG".bright_blue(),
+            display_path(&config_file.to_string_lossy())
+        );
+
+        // Count the number of each type of command and how many times it was run
+        let mut map: HashMap<String, (i32, i32)> = HashMap::new();
+        for (command, _) in commands.iter() {
+            let name = command.name();
+            map.insert(name.clone(), (map.get(&name).unwrap_or(&(0, 0)).0 + 1, 0));
+        }
+
+        let mut reports = Vec::new();
+        for (command, args) in commands.into_iter() {
+            let name = command.name();
+            let (total, ran) = *map.get(&name).unwrap();
+
+            info!(
+                "{} {} ({}/{})",
+                "RUNNING".green(),
+                name.bold(),
+                ran + 1,
+                total,
+            );
+            if let Err(err) = command.run(args, &app_context) {
+                return Err(BrixError::with(&format!(
+                    "Error running {} command in '{}'\n{}{}",
+                    name,
+                    display_path(&config_file.to_string_lossy()),
+                    err,
+                    rollback(&app_context)
+                )));
+            }
+
+            map.insert(name.clone(), (total, ran + 1));
+            reports.push(CommandReport {
+                name,
+                actions: vec![],
+            });
+        }
+        let elapsed = start.elapsed();
+
+        // Record what was generated so it can be undone with `brix undo`
+        let mut context = loader.context().cloned().unwrap_or_default();
+        context.extend(app_context.exports.borrow().clone());
+        let manifest = Manifest::record(&*self.fs, config, context.clone(), &app_context.journal)?;
+        let manifest_path = match manifest.files.is_empty() {
+            true => None,
+            false => {
+                let path = manifest.write(&*self.fs, &config.workdir)?;
+                debug!("wrote manifest to '{}'", path.display());
+                Some(path)
+            }
+        };
+
+        // Keep the answers so the config can be run again with `brix update`
+        let mut answers = Answers::read(&*self.fs, &config.workdir)?;
+        answers.upsert(ConfigAnswers {
+            language: config.language.clone(),
+            config: config.config_name.clone(),
+            answers: loader
+                .answers()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            context: context.clone().into_iter().collect(),
+        });
+        answers.write(&*self.fs, &config.workdir)?;
+
+        Ok(RunReport {
+            config_file,
+            commands: reports,
+            changes: app_context.journal.changes(),
+            context,
+            manifest: manifest_path,
+            elapsed,
+        })
+    }
+
+    fn loader(&self) -> ConfigLoader<'_> {
+        ConfigLoader::new(default_parsers(), &self.config).with_registry(self.registry.clone())
+    }
+
+    /// Finds the config files for the language and config name.
+    fn declarations(&self) -> Result<Vec<PathBuf>, BrixError> {
+        modules_from_config(&module_dir(&self.config), &self.config)
+    }
+
+    /// Runs the loader, adding the config file to any error.
+    fn load_commands(
+        &self,
+        loader: &mut ConfigLoader,
+        app_context: &AppContext,
+        config_file: &Path,
+    ) -> Result<CommandList, BrixError> {
+        loader.run(app_context).map_err(|err| {
+            BrixError::with(&format!(
+                "Error loading config at '{}':\n{}",
+                display_path(&config_file.to_string_lossy()),
+                err
+            ))
+        })
+    }
+}
+
+/// Restores every path the commands touched, unless rollback is disabled,
+/// and describes which paths were restored.
+fn rollback(app_context: &AppContext) -> String {
+    if app_context.config.no_rollback {
+        return String::new();
+    }
+
+    match app_context.journal.rollback() {
+        Ok(restored) if restored.is_empty() => String::new(),
+        Ok(restored) => {
+            let mut message = format!("\nRolled back {} path(s):", restored.len());
+            for path in restored.iter() {
+                message.push_str(&format!(
+                    "\n  restored '{}'",
+                    display_path(&path.to_string_lossy())
+                ));
+            }
+            message
+        }
+        Err(err) => format!("\nUnable to roll back: {}", err),
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use brix_commands::PlannedAction;
+use brix_common::{Filesystem, MemoryFilesystem};
+
+use crate::Generator;
+
+const CONFIG_DIR: &str = "crates/brix_core/generator/brix";
+
+fn config_dir() -> PathBuf {
+    PathBuf::from(CONFIG_DIR).canonicalize().unwrap()
+}
+
+/// A working directory in the system's temp directory, so that nothing is written to the repo.
+fn workdir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("brix-generator-{}-{}", name, std::process::id()))
+}
+
+fn generator(workdir: &Path) -> Generator {
+    Generator::new("txt", "note")
+        .config_dir(config_dir())
+        .workdir(workdir.to_path_buf())
+        .context_value("project", json!("app"))
+        .context_value("module", json!("users"))
+}
+
+#[test]
+fn plan() {
+    let workdir = workdir("plan");
+    let report = generator(&workdir).plan().unwrap();
+
+    assert_eq!(report.commands.len(), 1);
+    assert_eq!(report.commands[0].name, "template");
+    assert_eq!(
+        report.commands[0].actions,
+        vec![PlannedAction::Create(workdir.join("notes/users.txt"))]
+    );
+    assert!(report.changes.is_empty());
+    assert!(!workdir.exists());
+}
+
+#[test]
+fn run() {
+    let workdir = workdir("run");
+    let report = generator(&workdir).run();
+    let contents = fs::read_to_string(workdir.join("notes/users.txt"));
+    fs::remove_dir_all(&workdir).unwrap();
+
+    let report = report.unwrap();
+    assert_eq!(contents.unwrap(), "users by someone\n");
+    assert_eq!(report.context["module"], "users");
+    assert!(report.manifest.is_some());
+    assert!(report
+        .changes
+        .iter()
+        .any(|change| change.path() == workdir.join("notes/users.txt")));
+}
+
+#[test]
+fn in_memory() {
+    let workdir = workdir("in_memory");
+    let fs = Rc::new(MemoryFilesystem::over_disk());
+    generator(&workdir).filesystem(fs.clone()).run().unwrap();
+
+    assert_eq!(
+        fs.read_to_string(&workdir.join("notes/users.txt")).unwrap(),
+        "users by someone\n"
+    );
+    assert!(fs.is_file(&workdir.join(".brix-answers.yml")));
+    assert!(!workdir.exists());
+}
+
+#[test]
+fn missing_config() {
+    let err = Generator::new("txt", "missing")
+        .config_dir(config_dir())
+        .plan()
+        .unwrap_err();
+    assert!(err.message.contains("missing"));
+}
+
+#[test]
+fn generate_context() {
+    let workdir = workdir("generate_context");
+    let fs = Rc::new(MemoryFilesystem::over_disk());
+    Generator::new("txt", "notes")
+        .config_dir(config_dir())
+        .workdir(workdir.clone())
+        .context_value("project", json!("app"))
+        .context_value("module", json!("users"))
+        .filesystem(fs.clone())
+        .run()
+        .unwrap();
+
+    // Only the context declared on `generate` is passed on, so the note keeps its own author
+    assert_eq!(
+        fs.read_to_string(&workdir.join("notes/users-notes.txt"))
+            .unwrap(),
+        "users-notes by someone\n"
+    );
+}
+
+#[test]
+fn generate_cycle() {
+    let err = Generator::new("txt", "ping")
+        .config_dir(config_dir())
+        .context_value("project", json!("app"))
+        .context_value("module", json!("users"))
+        .plan()
+        .unwrap_err();
+    assert!(err
+        .message
+        .contains("config generate cycle detected: txt/ping -> txt/pong -> txt/ping"));
+}
+
+#[test]
+fn plugin_exports() {
+    let workdir = workdir("plugin_exports");
+    fs::create_dir_all(&workdir).unwrap();
+    let result = Generator::new("txt", "service")
+        .config_dir(config_dir())
+        .workdir(workdir.clone())
+        .context_value("project", json!("app"))
+        .context_value("module", json!("users"))
+        .run();
+    let contents = fs::read_to_string(workdir.join("notes/users-42.txt"));
+    fs::remove_dir_all(&workdir).unwrap();
+
+    // The template is rendered after the plugin exported the id
+    result.unwrap();
+    assert_eq!(contents.unwrap(), "users is service 42\n");
+}
@@ -0,0 +1,15 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! # Brix
+//! Runs Brix configs from Rust with [Generator], the same way as the `brix` binary.
+
+mod generator;
+mod report;
+
+pub mod util;
+
+pub use generator::Generator;
+pub use report::{CommandReport, RunReport};
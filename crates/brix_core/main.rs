@@ -5,25 +5,23 @@
 
 #![doc = include_str!("../../README.md")]
 
-use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
 use std::process;
-use std::time::Instant;
 
 use colored::*;
 
+use brix::util;
+use brix::{CommandReport, Generator, RunReport};
 use brix_cli::error as cli_error;
-use brix_common::AppContext;
-use brix_config_loader::YamlConfigParser;
-use brix_config_loader::{ConfigLoader, ParserList};
+use brix_cli::Mode;
+use brix_commands::PlannedAction;
+use brix_common::answers::{Answers, ANSWERS_FILE};
+use brix_common::history::Manifest;
+use brix_common::DiskFilesystem;
 use brix_errors::BrixError;
-use brix_processor::ProcessorCore;
-use log::{debug, error, info};
+use log::{debug, info};
 use simple_logger::SimpleLogger;
 
-mod util;
-
 type Result<T> = std::result::Result<T, BrixError>;
 
 fn main() {
@@ -52,158 +50,104 @@ fn try_main(matches: brix_cli::ArgMatches<'static>) -> Result<()> {
 
     debug!("HOME DIR: {:?}", home_dir);
 
-    let default_config = PathBuf::from(".config/brix");
-    let config_root = Path::new(config.config_dir.as_ref().unwrap_or(&default_config));
-    let language_dir = Path::new(&config.language);
-    let module_dir = config_root.join(language_dir);
-
-    let found_modules = modules_from_config(&module_dir, &config);
-    if found_modules.is_err() {
-        brix_cli::error!("{}", found_modules.unwrap_err());
-        process::exit(2);
+    match config.mode {
+        Mode::Undo { force } => undo(&config, force)?,
+        Mode::Update => update(&config)?,
+        Mode::Generate => generate(config)?,
     }
+    Ok(())
+}
 
-    let declarations = found_modules.unwrap();
-    let parsers: ParserList = vec![Box::new(YamlConfigParser {})];
-    let mut loader = ConfigLoader::new(parsers, &config);
-    let config_file = loader.load(declarations)?;
-
-    let processor = ProcessorCore::new();
-    let app_context = AppContext {
-        processor,
-        config: &config,
-    };
+/// Runs the config given by the CLI config with [Generator], steps 2 through 6 of the lifecycle.
+fn generate(config: brix_cli::Config) -> Result<()> {
+    let dry_run = config.dry_run;
+    let generator = Generator::from_config(config);
 
-    let start = Instant::now();
-    let commands = loader.run(&app_context).or_else(|err| {
-        return Err(BrixError::with(&format!(
-            "Error loading config at '{}':\n{}",
-            util::display_path(&config_file.to_string_lossy()),
-            err
-        )));
-    })?;
+    // Only print what would happen instead of running anything
+    if dry_run {
+        return print_plan(generator.plan()?);
+    }
 
+    let report = generator.run()?;
     info!(
-        "{} {}",
-        "CONFIG".bright_blue(),
-        util::display_path(&config_file.to_string_lossy())
+        "----------\n{} in {:#?}",
+        "DONE!".bright_green(),
+        report.elapsed
     );
-
-    // Count the number of each type of command and how many times it was run
-    let mut map: HashMap<String, (i32, i32)> = HashMap::new();
-    for (command, _) in commands.iter() {
-        let name = command.name();
-        map.insert(name.clone(), (map.get(&name).unwrap_or(&(0, 0)).0 + 1, 0));
-    }
-
-    for (command, args) in commands.into_iter() {
-        let name = command.name();
-        let (total, ran) = *map.get(&name).unwrap();
-
-        info!(
-            "{} {} ({}/{})",
-            "RUNNING".green(),
-            name.bold(),
-            ran + 1,
-            total,
-        );
-        if let Err(err) = command.run(args, &app_context) {
-            cli_error!(
-                "Error running {} command in '{}'",
-                command.name(),
-                util::display_path(&format!("{}", config_file.display()))
-            );
-            error!("{}", err);
-
-            process::exit(2);
-        }
-
-        map.insert(name, (total, ran + 1));
-    }
-    let elapsed = start.elapsed();
-
-    info!("----------\n{} in {:#?}", "DONE!".bright_green(), elapsed);
-    process::exit(0);
+    Ok(())
 }
 
-/// The main wrapper function for finding a module declaration file.
-/// Uses the `config_dir` to determine whether to search in parent directories or not.
-fn modules_from_config(dir: &PathBuf, config: &brix_cli::Config) -> Result<Vec<PathBuf>> {
-    let declarations;
-    if config.config_dir.is_none() {
-        declarations = search_for_module_declarations_all(dir.to_str().unwrap(), &config)?;
-    } else {
-        declarations = search_for_module_declarations(
-            config.config_dir.as_ref().unwrap(),
-            dir.to_str().unwrap(),
-            &config.config_name,
-        )?;
-    }
-
-    if declarations.len() == 0 {
+/// Runs every config in `.brix-answers.yml` again with the answers it was run with last time.
+/// Files that already exist have the changes merged into them instead of being overwritten.
+fn update(config: &brix_cli::Config) -> Result<()> {
+    let answers = Answers::read(&DiskFilesystem, &config.workdir)?;
+    if answers.configs.is_empty() {
         return Err(BrixError::with(&format!(
-            "Could not find module declaration for '{}' in {}",
-            config.config_name,
-            util::display_path(&dir.to_string_lossy())
+            "nothing to update, no answers found in '{}'",
+            util::display_path(&config.workdir.join(ANSWERS_FILE).to_string_lossy())
         )));
     }
 
-    Ok(declarations)
-}
-
-/// Uses `search_for_module_declarations` up to the home directory to find a module declaration.
-fn search_for_module_declarations_all(
-    path: &str,
-    config: &brix_cli::Config,
-) -> Result<Vec<PathBuf>> {
-    let mut current_path = config.workdir.clone();
-
-    loop {
-        debug!("Looking for config directory in {:?}", current_path);
-        let declarations =
-            search_for_module_declarations(&current_path, &path, &config.config_name)?;
-        if declarations.len() > 0 {
-            return Ok(declarations);
-        }
-
-        if &current_path == config.home_dir.as_ref().unwrap() {
-            return Err(BrixError::with(&format!(
-                "Could not find module declaration for '{}' in {}",
-                config.config_name,
-                util::display_path(&path)
-            )));
-        }
-
-        current_path = current_path.parent().unwrap().to_path_buf();
+    for recorded in answers.configs.into_iter() {
+        println!(
+            "{} {} {}",
+            "UPDATING".bright_blue(),
+            recorded.language,
+            recorded.config
+        );
+        let overrides = recorded.answers.into_iter().collect();
+        let update_config = config.for_config(&recorded.language, &recorded.config, overrides);
+        generate(update_config)?;
     }
+    Ok(())
 }
 
-/// Finds valid module declarations in the given directory.
-fn search_for_module_declarations(
-    current_path: &PathBuf,
-    path: &str,
-    name: &str,
-) -> Result<Vec<PathBuf>> {
-    let mut results = Vec::new();
-
-    let search_path = current_path.join(path);
-    if !search_path.exists() {
-        // Should not error if the directory doesn't exist,
-        // just return an empty vec of results
-        return Ok(vec![]);
+/// Takes back the last run using the latest manifest in `.brix/history`.
+fn undo(config: &brix_cli::Config, force: bool) -> Result<()> {
+    let (path, manifest) =
+        Manifest::latest(&DiskFilesystem, &config.workdir)?.ok_or_else(|| {
+            BrixError::with(&format!(
+                "nothing to undo, no history found in '{}'",
+                util::display_path(&config.workdir.to_string_lossy())
+            ))
+        })?;
+
+    let undone = manifest.undo(&DiskFilesystem, &config.workdir, force)?;
+    fs::remove_file(path)?;
+
+    println!(
+        "{} {} {}",
+        "UNDID".bright_blue(),
+        manifest.language,
+        manifest.config
+    );
+    for path in undone.iter() {
+        println!("  {}", util::display_path(&path.to_string_lossy()));
     }
+    Ok(())
+}
 
-    let paths = fs::read_dir(search_path)?;
-    for path in paths {
-        let path = path.unwrap().path();
-        if path.is_file() {
-            let stem = path.file_stem().unwrap();
-            if name == stem || format!("{}.brix", name) == stem.to_str().unwrap() {
-                results.push(path);
-            }
+/// Prints the actions each command would take for `--dry-run`.
+fn print_plan(report: RunReport) -> Result<()> {
+    println!("{}", "DRY RUN".bright_blue());
+    for CommandReport { name, actions } in report.commands.into_iter() {
+        println!("{}", name.bold());
+        if actions.is_empty() {
+            println!("  {}", "nothing to do".dimmed());
+        }
+        for action in actions.iter() {
+            let line = action.to_string();
+            let line = match action {
+                PlannedAction::Create(_) | PlannedAction::CreateDir(_) => line.green(),
+                PlannedAction::Overwrite(_) | PlannedAction::Ask(_) | PlannedAction::Merge(_) => {
+                    line.yellow()
+                }
+                PlannedAction::Skip(_) | PlannedAction::Ignore(_) => line.dimmed(),
+                PlannedAction::Remove(_) => line.red(),
+                _ => line.normal(),
+            };
+            println!("  {}", line);
         }
     }
-    info!("RESULTS: {:?}", results);
-
-    Ok(results)
+    Ok(())
 }
@@ -0,0 +1,38 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+//! Contains [RunReport], what [Generator](crate::Generator) returns after a run or plan.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use brix_commands::PlannedAction;
+use brix_common::journal::Change;
+
+/// A single command of the config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandReport {
+    pub name: String,
+    /// What the command would do, only filled in by [plan](crate::Generator::plan).
+    pub actions: Vec<PlannedAction>,
+}
+
+/// What a run did or what a plan would do.
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    /// The config file that was run.
+    pub config_file: PathBuf,
+    /// Every command in the order it ran or would run.
+    pub commands: Vec<CommandReport>,
+    /// Every path that was changed in the order they were first changed, empty for a plan.
+    pub changes: Vec<Change>,
+    /// The context shared by every command, along with anything exported by plugins.
+    pub context: HashMap<String, Value>,
+    /// The manifest written to `.brix/history`, if anything was changed.
+    pub manifest: Option<PathBuf>,
+    pub elapsed: Duration,
+}
@@ -63,6 +63,25 @@ impl From<serde_yaml::Error> for BrixError {
     }
 }
 
+impl From<serde_json::Error> for BrixError {
+    fn from(err: serde_json::Error) -> BrixError {
+        // serde_json already includes the line and column in its display message
+        BrixError {
+            kind: None,
+            message: format!("{}", err),
+        }
+    }
+}
+
+impl From<toml::de::Error> for BrixError {
+    fn from(err: toml::de::Error) -> BrixError {
+        BrixError {
+            kind: None,
+            message: format!("{}", err),
+        }
+    }
+}
+
 impl From<handlebars::RenderError> for BrixError {
     fn from(err: handlebars::RenderError) -> BrixError {
         BrixError {
@@ -104,6 +123,15 @@ impl From<fs_extra::error::Error> for BrixError {
     }
 }
 
+impl From<globset::Error> for BrixError {
+    fn from(err: globset::Error) -> BrixError {
+        BrixError {
+            kind: None,
+            message: format!("{}", err),
+        }
+    }
+}
+
 impl Display for BrixErrorKind {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         let formatted = match self {
@@ -11,6 +11,7 @@ use handlebars::{
     Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender, Output, RenderContext,
     RenderError,
 };
+use serde::Serialize;
 use serde_json::json;
 use serde_json::value::{Map, Value as Json};
 use std::collections::HashMap;
@@ -45,10 +46,50 @@ impl<'a> ProcessorCore<'a> {
         let result = self.handlebars.render_template(&text, &context)?;
         Ok(result)
     }
+
+    /// Evaluates a condition against the provided context.
+    /// Conditions containing a template such as `{{with_tests}}` are rendered and the output is checked,
+    /// anything else is treated as a handlebars expression e.g. `(and with_tests (eq build "gradle"))`.
+    pub fn evaluate(&self, condition: &str, context: Map<String, Json>) -> Result<bool, BrixError> {
+        if condition.contains("{{") {
+            let rendered = self.process(String::from(condition), context)?;
+            return Ok(!matches!(rendered.trim(), "" | "false" | "0" | "null"));
+        }
+
+        let template = format!("{{{{#if {}}}}}true{{{{/if}}}}", condition.trim());
+        Ok(self.process(template, context)? == "true")
+    }
+
+    /// Render every string inside of a JSON value with the provided context.
+    /// Arrays and objects are walked recursively, all other values are left untouched.
+    pub fn process_value(
+        &self,
+        value: Json,
+        context: &Map<String, Json>,
+    ) -> Result<Json, BrixError> {
+        let result = match value {
+            Json::String(text) => Json::String(self.process(text, context.clone())?),
+            Json::Array(items) => Json::Array(
+                items
+                    .into_iter()
+                    .map(|item| self.process_value(item, context))
+                    .collect::<Result<Vec<Json>, BrixError>>()?,
+            ),
+            Json::Object(map) => {
+                let mut processed = Map::new();
+                for (key, item) in map.into_iter() {
+                    processed.insert(key, self.process_value(item, context)?);
+                }
+                Json::Object(processed)
+            }
+            other => other,
+        };
+        Ok(result)
+    }
 }
 
 /// Create a valid context map by serializing into JSON.
-pub fn create_context(data: HashMap<String, String>) -> Map<String, Json> {
+pub fn create_context<V: Serialize>(data: HashMap<String, V>) -> Map<String, Json> {
     let mut res = Map::new();
     for (key, value) in data.into_iter() {
         res.insert(key, json!(value));
@@ -3,6 +3,9 @@
 // This software is released under the MIT License.
 // https://opensource.org/licenses/MIT
 
+// Not every test uses every helper in here
+#![allow(dead_code)]
+
 use brix_processor::ProcessorCore;
 use std::fs::read_to_string;
 use std::path::PathBuf;
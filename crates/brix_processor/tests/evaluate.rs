@@ -0,0 +1,50 @@
+// Copyright (c) 2021 Ethan Lerner, Caleb Cushing, and the Brix contributors
+//
+// This software is released under the MIT License.
+// https://opensource.org/licenses/MIT
+
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+mod common;
+
+lazy_static! {
+    static ref EVALUATE_CONTEXT: HashMap<String, Value> = {
+        let mut map = HashMap::new();
+        map.insert(String::from("with_tests"), json!(true));
+        map.insert(String::from("skip"), json!(false));
+        map.insert(String::from("build"), json!("gradle"));
+        map.insert(String::from("count"), json!(0));
+        map.insert(String::from("items"), json!(["one"]));
+        map
+    };
+    static ref EVALUATE_ASSERTIONS: Vec<(&'static str, bool)> = vec![
+        ("with_tests", true),
+        ("skip", false),
+        ("missing", false),
+        ("count", false),
+        ("items", true),
+        ("true", true),
+        ("(not skip)", true),
+        ("(eq build \"gradle\")", true),
+        ("(eq build \"maven\")", false),
+        ("(and with_tests (not skip))", true),
+        ("(or skip (eq build \"maven\"))", false),
+        ("{{with_tests}}", true),
+        ("{{skip}}", false),
+        ("{{missing}}", false),
+        ("{{#if skip}}yes{{/if}}", false),
+    ];
+}
+
+#[test]
+fn evaluate() {
+    let core = common::setup();
+    let context = brix_processor::create_context(EVALUATE_CONTEXT.clone());
+
+    for (condition, expected) in EVALUATE_ASSERTIONS.iter() {
+        let result = core.evaluate(condition, context.clone()).unwrap();
+        assert_eq!(result, *expected, "{}", condition);
+    }
+}